@@ -0,0 +1,5 @@
+fn main() {
+    // `runner::days!` and `runner::run_puzzle!` scan `src/` for `dayN.rs`
+    // modules, so adding a new day has to trigger a rebuild.
+    println!("cargo:rerun-if-changed=src");
+}
@@ -1,7 +1,125 @@
 extern crate proc_macro;
 extern crate syn;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use std::path::PathBuf;
+
+struct Day {
+    number: u32,
+    path: PathBuf,
+}
+
+impl Day {
+    fn module(&self) -> syn::Ident {
+        format_ident!("day{}", self.number)
+    }
+
+    fn solution(&self) -> syn::Ident {
+        format_ident!("Day{}", self.number)
+    }
+
+    /// Whether the module's source file contains an
+    /// `impl Solution for DayN` block.
+    fn implements_solution(&self) -> Result<bool, String> {
+        let source = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("could not read {:?}: {e}", self.path))?;
+        let file = syn::parse_file(&source)
+            .map_err(|e| format!("could not parse {:?}: {e}", self.path))?;
+
+        let solution = self.solution().to_string();
+        let is_ident = |path: &syn::Path, name: &str| {
+            path.segments.last().is_some_and(|s| s.ident == name)
+        };
+
+        Ok(file.items.iter().any(|item| match item {
+            syn::Item::Impl(syn::ItemImpl {
+                trait_: Some((None, trait_path, _)),
+                self_ty,
+                ..
+            }) => {
+                is_ident(trait_path, "Solution")
+                    && matches!(
+                        self_ty.as_ref(),
+                        syn::Type::Path(ty)
+                            if is_ident(&ty.path, &solution)
+                    )
+            }
+            _ => false,
+        }))
+    }
+}
+
+/// Finds every `src/dayN.rs` module of the crate invoking the macro, sorted
+/// by day number.
+fn discover_days() -> Result<Vec<Day>, String> {
+    let src = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .map_err(|_| "CARGO_MANIFEST_DIR is not set".to_string())?,
+    )
+    .join("src");
+
+    let mut days: Vec<Day> = std::fs::read_dir(&src)
+        .map_err(|e| format!("could not read {:?}: {e}", src))?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+            let number = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix("day")?
+                .strip_suffix(".rs")?
+                .parse()
+                .ok()?;
+            Some(Day { number, path })
+        })
+        .collect();
+    days.sort_by_key(|day| day.number);
+
+    let errors: Vec<String> = days
+        .iter()
+        .filter_map(|day| match day.implements_solution() {
+            Ok(true) => None,
+            Ok(false) => Some(format!(
+                "module `{}` does not implement `Solution` for `{}`",
+                day.module(),
+                day.solution()
+            )),
+            Err(e) => Some(e),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(days)
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    quote! { compile_error!(#message); }.into()
+}
+
+/// Declares a `pub mod dayN` for every `src/dayN.rs` file along with a
+/// `DAYS` registry listing their numbers.
+#[proc_macro]
+pub fn days(_tokens: TokenStream) -> TokenStream {
+    let days = match discover_days() {
+        Ok(days) => days,
+        Err(e) => return compile_error(&e),
+    };
+
+    let modules = days.iter().map(Day::module);
+    let numbers = days.iter().map(|day| day.number as u8);
+
+    let expanded = quote! {
+        #(pub mod #modules;)*
+
+        /// Every day with a solution module, in ascending order.
+        pub const DAYS: &[u8] = &[#(#numbers),*];
+    };
+
+    expanded.into()
+}
 
 #[proc_macro]
 pub fn run_puzzle(tokens: TokenStream) -> TokenStream {
@@ -13,37 +131,38 @@ pub fn run_puzzle(tokens: TokenStream) -> TokenStream {
     let get_puzzle_fn = &args[0];
     let day_lit = &args[1];
     let part_lit = &args[2];
+    let timeout = &args[3];
 
-    let path_names = |day: &str| {
-        let path = format!("advent_of_code_2023::day{day}");
-        (
-            syn::parse_str(&format!("{}::part1", path)).unwrap(),
-            syn::parse_str(&format!("{}::part2", path)).unwrap(),
-        )
+    let days = match discover_days() {
+        Ok(days) => days,
+        Err(e) => return compile_error(&e),
     };
 
-    let modules: Vec<_> = (1..=5)
+    let modules: Vec<_> = days
+        .iter()
         .map(|day| {
-            let day_str = day.to_string();
-            let day = day_str.as_str();
-            let (part1, part2): (syn::Path, syn::Path) = path_names(day);
+            let number = day.number as u8;
+            let module = day.module();
+            let solution = day.solution();
 
             quote! {
-                #day => {
-                    match #part_lit.as_str() {
-                        "1" => { #part1(&input) },
-                        "2" => { #part2(&input) },
-                        _ => { panic!("Invalid part") }
-                    }
-                },
+                #number => {
+                    let _span = advent_of_code_2023::logging::Span::day(
+                        #number,
+                    )
+                    .enter();
+                    advent_of_code_2023::solution::run::<
+                        advent_of_code_2023::#module::#solution,
+                    >(&input, #part_lit, #timeout)
+                }
             }
         })
         .collect();
 
     let expanded = quote! {
         {
-            let input = #get_puzzle_fn(day);
-            match #day_lit.as_str() {
+            let input = #get_puzzle_fn(#day_lit);
+            match #day_lit {
                 #(#modules)*
                 _ => panic!("Day not found"),
             }
@@ -0,0 +1,95 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// A puzzle answer -- an integer of any width, or text.
+///
+/// Non-negative integers are always stored as `Unsigned` so that answers
+/// compare equal regardless of the type the solution computed them in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Text(String),
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Unsigned(n as u128)
+            }
+        })*
+    };
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                if n < 0 {
+                    Answer::Signed(n as i128)
+                } else {
+                    Answer::Unsigned(n as u128)
+                }
+            }
+        })*
+    };
+}
+
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ok(match (s.parse::<u128>(), s.parse::<i128>()) {
+            (Ok(n), _) => n.into(),
+            (_, Ok(n)) => n.into(),
+            _ => s.into(),
+        })
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{n}"),
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_compare_across_widths() {
+        assert_eq!(Answer::from(42_u8), Answer::from(42_i64));
+        assert_eq!(Answer::from(u64::MAX), Answer::from(u64::MAX as u128));
+        assert_ne!(Answer::from(-1_i32), Answer::from(u128::MAX));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("389056265".parse(), Ok(Answer::from(389056265_u64)));
+        assert_eq!("-7".parse(), Ok(Answer::from(-7_i8)));
+        assert_eq!(" JXBEG\n".parse(), Ok(Answer::from("JXBEG")));
+    }
+}
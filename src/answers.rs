@@ -0,0 +1,380 @@
+use crate::input::Checksum;
+use crate::solution::Part;
+use crate::Answer;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// What the website said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "wrong" => Ok(Verdict::Wrong),
+            _ => Err(format!("invalid verdict {s:?}")),
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too-high"),
+            Verdict::TooLow => write!(f, "too-low"),
+            Verdict::Wrong => write!(f, "wrong"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: Part,
+    /// The checksum of the input the answer was computed from. Entries
+    /// recorded before answers were keyed by input have none, and are taken
+    /// to be for the profile's own cached input.
+    pub input: Option<Checksum>,
+    pub verdict: Verdict,
+    pub answer: Answer,
+    /// Whether computing the answer takes long enough that regression runs
+    /// skip it unless asked not to.
+    pub slow: bool,
+}
+
+impl FromStr for Entry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid answer entry {s:?}");
+        let mut fields = s.split_whitespace();
+        let mut next = || fields.next().ok_or_else(invalid);
+
+        let day = next()?.parse().map_err(|_| invalid())?;
+        let part = next()?.parse()?;
+        // Entries written before answers were keyed by input go straight
+        // on to the verdict.
+        let (input, verdict) = match next()? {
+            field if field.parse::<Verdict>().is_ok() => (None, field),
+            "-" => (None, next()?),
+            field => (Some(field.parse()?), next()?),
+        };
+        let entry = Self {
+            day,
+            part,
+            input,
+            verdict: verdict.parse()?,
+            answer: next()?.parse().map_err(|_| invalid())?,
+            slow: false,
+        };
+
+        fields.try_fold(entry, |entry, flag| match flag {
+            "slow" => Ok(Entry {
+                slow: true,
+                ..entry
+            }),
+            _ => Err(format!("invalid flag {flag:?} in {s:?}")),
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.day, self.part)?;
+        match self.input {
+            Some(input) => write!(f, "{input}")?,
+            None => write!(f, "-")?,
+        }
+        write!(f, " {} {}", self.verdict, self.answer)?;
+        if self.slow {
+            write!(f, " slow")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every answer submitted so far, kept in a
+/// `day part input verdict answer [slow]` text file.
+#[derive(Debug)]
+pub struct Answers {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl Answers {
+    /// Reads the answers file at `path`, which need not exist yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("could not read {path:?}: {e}")),
+        };
+
+        let entries = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                !line.trim().is_empty() && !line.trim().starts_with('#')
+            })
+            .map(|(i, line)| {
+                line.parse().map_err(|e| format!("{path:?}:{}: {e}", i + 1))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut contents =
+            String::from("# day part input verdict answer [slow]\n");
+        for entry in &self.entries {
+            contents.push_str(&format!("{entry}\n"));
+        }
+        std::fs::write(&self.path, contents)
+            .map_err(|e| format!("could not write {:?}: {e}", self.path))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The entries for `part` of `day` that are for `input`: those keyed to
+    /// it, then, if `own` says it is the profile's own cached input, those
+    /// not keyed to any input.
+    fn for_input(
+        &self,
+        day: u8,
+        part: Part,
+        input: Checksum,
+        own: bool,
+    ) -> impl Iterator<Item = &Entry> {
+        let keyed = |key: Option<Checksum>| {
+            self.entries.iter().filter(move |e| {
+                e.day == day && e.part == part && e.input == key
+            })
+        };
+        keyed(Some(input)).chain(keyed(None).filter(move |_| own))
+    }
+
+    /// Whether any input has an accepted answer for `part` of `day`.
+    pub fn any_accepted(&self, day: u8, part: Part) -> bool {
+        self.entries.iter().any(|e| {
+            e.day == day && e.part == part && e.verdict == Verdict::Correct
+        })
+    }
+
+    /// The accepted entry for `part` of `day` on `input`, if there is one.
+    /// Entries without an input only count if `own` says `input` is the
+    /// profile's own cached input.
+    pub fn accepted(
+        &self,
+        day: u8,
+        part: Part,
+        input: Checksum,
+        own: bool,
+    ) -> Option<&Entry> {
+        self.for_input(day, part, input, own)
+            .find(|e| e.verdict == Verdict::Correct)
+    }
+
+    /// What is already known about `answer` on `input` without submitting
+    /// it: its recorded verdict, or `Wrong` if a different answer was
+    /// accepted for the same input. `own` is as for [`Answers::accepted`].
+    pub fn check(
+        &self,
+        day: u8,
+        part: Part,
+        input: Checksum,
+        own: bool,
+        answer: &Answer,
+    ) -> Option<Verdict> {
+        let recorded = self
+            .for_input(day, part, input, own)
+            .find(|e| &e.answer == answer);
+        let accepted = self.accepted(day, part, input, own);
+
+        match (recorded, accepted) {
+            (Some(entry), _) => Some(entry.verdict),
+            (None, Some(_)) => Some(Verdict::Wrong),
+            (None, None) => None,
+        }
+    }
+
+    /// Adds `entry`, replacing any earlier verdict on the same answer for
+    /// the same or no input. An accepted answer stays marked slow if the
+    /// part was marked slow before.
+    pub fn record(&mut self, mut entry: Entry) {
+        entry.slow |= self
+            .entries
+            .iter()
+            .any(|e| e.day == entry.day && e.part == entry.part && e.slow);
+        self.entries.retain(|e| {
+            (e.day, e.part, &e.answer) != (entry.day, entry.part, &entry.answer)
+                || e.input.is_some_and(|input| Some(input) != entry.input)
+        });
+        self.entries.push(entry);
+        self.entries.sort_by_key(|e| (e.day, e.part));
+    }
+
+    /// Keys the entries for `part` of `day` that have no input to `input`,
+    /// once an answer computed from it matched the one accepted among them.
+    /// Returns whether there were any.
+    pub fn adopt(&mut self, day: u8, part: Part, input: Checksum) -> bool {
+        let mut adopted = false;
+        for e in &mut self.entries {
+            if e.day == day && e.part == part && e.input.is_none() {
+                e.input = Some(input);
+                adopted = true;
+            }
+        }
+        adopted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Input;
+
+    fn entry(day: u8, part: Part, verdict: Verdict, answer: u64) -> Entry {
+        Entry {
+            day,
+            part,
+            input: None,
+            verdict,
+            answer: answer.into(),
+            slow: false,
+        }
+    }
+
+    #[test]
+    fn parse_entry() {
+        assert_eq!(
+            "5 2 too-low 12345".parse(),
+            Ok(entry(5, Part::Two, Verdict::TooLow, 12345))
+        );
+        assert_eq!(
+            "1 1 correct JXBEG".parse::<Entry>().map(|e| e.answer),
+            Ok(Answer::from("JXBEG"))
+        );
+        assert_eq!(
+            "5 2 correct 46 slow".parse(),
+            Ok(Entry {
+                slow: true,
+                ..entry(5, Part::Two, Verdict::Correct, 46)
+            })
+        );
+        assert_eq!(
+            "5 2 e71fa2190541574b correct 46".parse(),
+            Ok(Entry {
+                input: Some(Input::from("abc").checksum()),
+                ..entry(5, Part::Two, Verdict::Correct, 46)
+            })
+        );
+        assert_eq!(
+            "5 2 - too-low 12345".parse(),
+            Ok(entry(5, Part::Two, Verdict::TooLow, 12345))
+        );
+        assert!("5 2 correct 46 fast".parse::<Entry>().is_err());
+        assert!("5 2 e71fa219 correct 46".parse::<Entry>().is_err());
+        assert!("1 3 correct 5".parse::<Entry>().is_err());
+        assert!("1 1 maybe 5".parse::<Entry>().is_err());
+        assert!("1 1".parse::<Entry>().is_err());
+    }
+
+    #[test]
+    fn check() {
+        let path = std::env::temp_dir().join("aoc-answers-check");
+        let mut answers = Answers::load(&path).unwrap();
+        answers.record(entry(1, Part::One, Verdict::TooHigh, 100));
+        answers.record(entry(1, Part::Two, Verdict::Correct, 42));
+
+        let mine = Input::from("mine").checksum();
+        answers.record(Entry {
+            input: Some(mine),
+            ..entry(2, Part::One, Verdict::Correct, 7)
+        });
+
+        let check = |day, part, answer: u64| {
+            answers.check(day, part, mine, true, &answer.into())
+        };
+        assert_eq!(check(1, Part::One, 100), Some(Verdict::TooHigh));
+        assert_eq!(check(1, Part::One, 99), None);
+        assert_eq!(check(1, Part::Two, 42), Some(Verdict::Correct));
+        assert_eq!(check(1, Part::Two, 41), Some(Verdict::Wrong));
+        assert_eq!(check(2, Part::One, 7), Some(Verdict::Correct));
+        assert_eq!(check(2, Part::One, 8), Some(Verdict::Wrong));
+
+        // Entries without an input are only for the profile's own input.
+        assert_eq!(answers.check(1, Part::Two, mine, false, &41.into()), None);
+        let theirs = Input::from("theirs").checksum();
+        assert_eq!(answers.check(2, Part::One, theirs, true, &8.into()), None);
+    }
+
+    #[test]
+    fn keyed_by_input() {
+        let path = std::env::temp_dir().join("aoc-answers-keyed-by-input");
+        let mut answers = Answers::load(&path).unwrap();
+        answers.record(entry(1, Part::One, Verdict::TooLow, 40));
+        answers.record(entry(1, Part::One, Verdict::Correct, 42));
+
+        let mine = Input::from("mine").checksum();
+        let theirs = Input::from("theirs").checksum();
+        let expected = |answers: &Answers, input, own| {
+            answers
+                .accepted(1, Part::One, input, own)
+                .map(|e| e.answer.clone())
+        };
+        assert_eq!(expected(&answers, mine, true), Some(42.into()));
+        assert_eq!(expected(&answers, theirs, false), None);
+
+        assert!(answers.adopt(1, Part::One, mine));
+        assert!(!answers.adopt(1, Part::One, mine));
+        assert!(answers.entries().iter().all(|e| e.input == Some(mine)));
+        assert_eq!(expected(&answers, mine, false), Some(42.into()));
+        assert_eq!(expected(&answers, theirs, true), None);
+        assert!(answers.any_accepted(1, Part::One));
+
+        answers.record(Entry {
+            input: Some(theirs),
+            ..entry(1, Part::One, Verdict::Correct, 42)
+        });
+        assert_eq!(answers.entries().len(), 3);
+        assert_eq!(expected(&answers, theirs, false), Some(42.into()));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("aoc-answers-save-and-load");
+        let mut answers = Answers::load(&path).unwrap();
+        answers.record(entry(3, Part::One, Verdict::Wrong, 7));
+        answers.record(entry(1, Part::Two, Verdict::Correct, 9));
+        answers.record(entry(3, Part::One, Verdict::TooLow, 7));
+        answers.save().unwrap();
+
+        let loaded = Answers::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            loaded.entries(),
+            [
+                entry(1, Part::Two, Verdict::Correct, 9),
+                entry(3, Part::One, Verdict::TooLow, 7),
+            ]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::report::align;
+use crate::solution::{Output, Part};
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// Summary statistics over repeated timings of one stage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len().max(1) as f64;
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        let median = match sorted.len() {
+            0 => Duration::ZERO,
+            len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2,
+            len => sorted[len / 2],
+        };
+
+        Self {
+            min: sorted.first().copied().unwrap_or_default(),
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Parse and solve statistics for one part of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub day: u8,
+    pub part: Part,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Measurement {
+    pub fn from(day: u8, part: Part, runs: &[Output]) -> Self {
+        let times = |f: fn(&Output) -> Duration| -> Vec<Duration> {
+            runs.iter().map(f).collect()
+        };
+
+        Self {
+            day,
+            part,
+            parse: Stats::from(&times(|o| o.parse_time)),
+            solve: Stats::from(&times(|o| o.solve_time)),
+        }
+    }
+
+    fn stages(&self) -> [(&'static str, &Stats); 2] {
+        [("parse", &self.parse), ("solve", &self.solve)]
+    }
+}
+
+/// Writes `measurements` to `path`, one `day part stage min median mean
+/// stddev` line per stage with every duration in nanoseconds.
+pub fn save(path: &Path, measurements: &[Measurement]) -> std::io::Result<()> {
+    let mut contents =
+        String::from("# day part stage min median mean stddev\n");
+    for m in measurements {
+        for (stage, stats) in m.stages() {
+            writeln!(
+                contents,
+                "{} {} {stage} {} {} {} {}",
+                m.day,
+                m.part,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            )
+            .unwrap();
+        }
+    }
+    std::fs::write(path, contents)
+}
+
+/// Reads measurements previously written by [`save`].
+pub fn load(path: &Path) -> Result<Vec<Measurement>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("could not read {path:?}: {e}"))?;
+
+    let mut measurements: Vec<Measurement> = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || format!("{path:?}:{}: invalid line {line:?}", i + 1);
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [day, part, stage, min, median, mean, stddev] = fields[..] else {
+            return Err(invalid());
+        };
+
+        let nanos = |s: &str| s.parse().map(Duration::from_nanos);
+        let stats = Stats {
+            min: nanos(min).map_err(|_| invalid())?,
+            median: nanos(median).map_err(|_| invalid())?,
+            mean: nanos(mean).map_err(|_| invalid())?,
+            stddev: nanos(stddev).map_err(|_| invalid())?,
+        };
+        let day: u8 = day.parse().map_err(|_| invalid())?;
+        let part: Part = part.parse().map_err(|_| invalid())?;
+
+        let index = match measurements
+            .iter()
+            .position(|m| m.day == day && m.part == part)
+        {
+            Some(index) => index,
+            None => {
+                measurements.push(Measurement {
+                    day,
+                    part,
+                    parse: stats,
+                    solve: stats,
+                });
+                measurements.len() - 1
+            }
+        };
+        match stage {
+            "parse" => measurements[index].parse = stats,
+            "solve" => measurements[index].solve = stats,
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// Lays `measurements` out as a table, with the change in median against
+/// `baseline` when one is given.
+pub fn table(
+    measurements: &[Measurement],
+    baseline: Option<&[Measurement]>,
+) -> String {
+    let mut header =
+        vec!["day", "part", "stage", "min", "median", "mean", "stddev"];
+    if baseline.is_some() {
+        header.push("vs baseline");
+    }
+
+    let rows: Vec<Vec<String>> = measurements
+        .iter()
+        .flat_map(|m| {
+            m.stages().map(|(stage, stats)| {
+                let mut row = vec![
+                    m.day.to_string(),
+                    m.part.to_string(),
+                    stage.to_string(),
+                    format!("{:.2?}", stats.min),
+                    format!("{:.2?}", stats.median),
+                    format!("{:.2?}", stats.mean),
+                    format!("{:.2?}", stats.stddev),
+                ];
+                if let Some(baseline) = baseline {
+                    row.push(compare(m, stage, stats, baseline));
+                }
+                row
+            })
+        })
+        .collect();
+
+    align(&header, &rows).join("\n")
+}
+
+fn compare(
+    m: &Measurement,
+    stage: &str,
+    stats: &Stats,
+    baseline: &[Measurement],
+) -> String {
+    let Some(before) = baseline
+        .iter()
+        .find(|b| b.day == m.day && b.part == m.part)
+        .and_then(|b| b.stages().into_iter().find(|(s, _)| *s == stage))
+        .map(|(_, stats)| stats.median)
+    else {
+        return "-".to_string();
+    };
+
+    if before.is_zero() {
+        return "-".to_string();
+    }
+    let change = stats.median.as_secs_f64() / before.as_secs_f64() - 1.0;
+    format!("{:+.1}%", change * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from(&[ms(4), ms(1), ms(3), ms(2)]);
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+
+        let stats = Stats::from(&[ms(5), ms(9), ms(1)]);
+        assert_eq!(stats.median, ms(5));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let measurements = vec![
+            Measurement {
+                day: 1,
+                part: Part::One,
+                parse: Stats::from(&[ms(1), ms(2)]),
+                solve: Stats::from(&[ms(3), ms(5)]),
+            },
+            Measurement {
+                day: 5,
+                part: Part::Two,
+                parse: Stats::from(&[ms(7)]),
+                solve: Stats::from(&[ms(11), ms(13), ms(17)]),
+            },
+        ];
+
+        let path = std::env::temp_dir().join("aoc-bench-save-and-load");
+        save(&path, &measurements).unwrap();
+        let loaded = load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, Ok(measurements));
+    }
+}
@@ -0,0 +1,442 @@
+use crate::input::InputError;
+use crate::solution::Part;
+use crate::Answer;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+const BASE_URL: &str = "https://adventofcode.com";
+
+/// How the website responded to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    /// Another answer was submitted too recently.
+    RateLimited(Duration),
+    /// The part has already been solved, or part 1 hasn't been yet.
+    WrongLevel,
+    /// A response none of the above matched, reduced to its text.
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Reads the outcome out of the `<article>` of an answer response page.
+    pub fn parse(html: &str) -> Self {
+        let text = article_text(html);
+
+        if text.contains("That's the right answer") {
+            Outcome::Correct
+        } else if text.contains("That's not the right answer") {
+            if text.contains("your answer is too high") {
+                Outcome::TooHigh
+            } else if text.contains("your answer is too low") {
+                Outcome::TooLow
+            } else {
+                Outcome::Wrong
+            }
+        } else if text.contains("You gave an answer too recently") {
+            Outcome::RateLimited(wait_time(&text).unwrap_or_default())
+        } else if text.contains("You don't seem to be solving the right level")
+        {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown(text)
+        }
+    }
+}
+
+/// The text inside the page's `<article>`, or the whole page if it has
+/// none, with every tag removed.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map(|(article, _)| article)
+        .unwrap_or(html);
+
+    strip_tags(article)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `html` with every tag removed, leaving whitespace as it is.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text
+}
+
+/// Decodes the few character references that show up in puzzle text.
+fn unescape(text: &str) -> String {
+    [
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#39;", "'"),
+        ("&apos;", "'"),
+        ("&amp;", "&"),
+    ]
+    .iter()
+    .fold(text.to_string(), |text, (entity, c)| {
+        text.replace(entity, c)
+    })
+}
+
+/// Every distinct `<pre><code>` block of a puzzle page, in order of
+/// appearance. Most are example inputs, but some show intermediate states
+/// of a worked example instead.
+pub fn examples(html: &str) -> Vec<String> {
+    let mut examples: Vec<String> = Vec::new();
+    for block in html.split("<pre><code>").skip(1) {
+        let Some((code, _)) = block.split_once("</code></pre>") else {
+            continue;
+        };
+        let example = unescape(&strip_tags(code));
+        if !example.trim().is_empty() && !examples.contains(&example) {
+            examples.push(example);
+        }
+    }
+    examples
+}
+
+/// Parses the `You have 1m 23s left to wait` part of a rate limit message.
+fn wait_time(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+
+    wait.split_whitespace()
+        .try_fold(Duration::ZERO, |total, unit| {
+            let (n, suffix) =
+                unit.split_at(unit.find(|c: char| !c.is_numeric())?);
+            let n: u64 = n.parse().ok()?;
+            let seconds = match suffix {
+                "h" => n * 60 * 60,
+                "m" => n * 60,
+                "s" => n,
+                _ => return None,
+            };
+            Some(total + Duration::from_secs(seconds))
+        })
+}
+
+/// Looks up the session cookie in `AOC_SESSION`, then in a `.session` file
+/// in the working directory, then in one in the crate root. Either the bare
+/// cookie value or a whole `session=<value>` pair is accepted.
+pub fn session() -> Result<String, InputError> {
+    if let Ok(session) = std::env::var("AOC_SESSION") {
+        return cookie(&session);
+    }
+    let path = [
+        Path::new(".session"),
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.session")),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+    .ok_or(InputError::MissingSession)?;
+    session_file(path)
+}
+
+/// Reads the session cookie from the file at `path`, in either form
+/// [`session`] accepts.
+pub fn session_file(path: &Path) -> Result<String, InputError> {
+    let session =
+        std::fs::read_to_string(path).map_err(|e| InputError::io(path, e))?;
+    cookie(&session)
+}
+
+fn cookie(session: &str) -> Result<String, InputError> {
+    match session.trim() {
+        "" => Err(InputError::MissingSession),
+        s if s.starts_with("session=") => Ok(s.to_string()),
+        s => Ok(format!("session={s}")),
+    }
+}
+
+/// An authenticated session on the Advent of Code website.
+pub struct Client {
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    /// `session` is the value of the `Cookie` header to authenticate with.
+    ///
+    /// Requests go to the website unless `AOC_BASE_URL` points elsewhere,
+    /// such as a local mock server.
+    pub fn new(session: &str) -> Self {
+        match std::env::var("AOC_BASE_URL") {
+            Ok(base_url) => Self::with_base_url(session, &base_url),
+            Err(_) => Self::with_base_url(session, BASE_URL),
+        }
+    }
+
+    pub fn with_base_url(session: &str, base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+        }
+    }
+
+    pub fn fetch_input(&self, day: u8) -> Result<String, InputError> {
+        self.get(day, &format!("/2023/day/{day}/input"))
+    }
+
+    /// The puzzle description page, which covers part 2 as well once part
+    /// 1 has been solved.
+    pub fn fetch_puzzle(&self, day: u8) -> Result<String, InputError> {
+        self.get(day, &format!("/2023/day/{day}"))
+    }
+
+    fn get(&self, day: u8, path: &str) -> Result<String, InputError> {
+        let url = format!("{}{path}", self.base_url);
+        let response = match ureq::get(&url).set("Cookie", &self.session).call()
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => {
+                return Err(InputError::NotUnlocked { day })
+            }
+            Err(ureq::Error::Status(status, _)) => {
+                return Err(InputError::Http { day, status })
+            }
+            Err(e) => {
+                return Err(InputError::Transport {
+                    day,
+                    message: e.to_string(),
+                })
+            }
+        };
+
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .map_err(|e| InputError::Transport {
+                day,
+                message: e.to_string(),
+            })?;
+        String::from_utf8(bytes)
+            .map_err(|_| InputError::InvalidUtf8 { origin: url })
+    }
+
+    pub fn submit(
+        &self,
+        day: u8,
+        part: Part,
+        answer: &Answer,
+    ) -> Result<Outcome, String> {
+        let url = format!("{}/2023/day/{day}/answer", self.base_url);
+        let response = ureq::post(&url)
+            .set("Cookie", &self.session)
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ])
+            .map_err(|e| format!("could not submit answer: {e}"))?
+            .into_string()
+            .map_err(|e| format!("could not read response: {e}"))?;
+
+        Ok(Outcome::parse(&response))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    fn page(article: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{article}</p></article>\
+            </main></body></html>"
+        )
+    }
+
+    #[test]
+    fn parse_outcomes() {
+        let parse = |article: &str| Outcome::parse(&page(article));
+
+        assert_eq!(
+            parse("That's the right answer! You are one gold star closer."),
+            Outcome::Correct
+        );
+        assert_eq!(
+            parse(
+                "That's not the right answer; your answer is too high. \
+                Please wait one minute before trying again."
+            ),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low."),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse("That's not the right answer. If you're stuck, ..."),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            parse(
+                "You gave an answer too recently; you have to wait after \
+                submitting an answer before trying again.  You have \
+                <span>1m 23s</span> left to wait."
+            ),
+            Outcome::RateLimited(Duration::from_secs(83))
+        );
+        assert_eq!(
+            parse(
+                "You don't seem to be solving the right level.  Did you \
+                already complete it?"
+            ),
+            Outcome::WrongLevel
+        );
+        assert_eq!(
+            parse("Something <em>else</em>"),
+            Outcome::Unknown("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn extract_examples() {
+        let day1 = include_str!("../fixtures/day1.html");
+        assert_eq!(
+            examples(day1),
+            [
+                "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n",
+                "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+                4nineeightseven2\nzoneight234\n7pqrstsixteen\n",
+            ]
+        );
+
+        let day3 = include_str!("../fixtures/day3.html");
+        assert_eq!(
+            examples(day3),
+            [
+                "467..114..\n...*......\n..35..633.\n......#...\n617*......\n\
+            .....+.58.\n..592.....\n......755.\n...$.*....\n.664.598..\n"
+            ]
+        );
+
+        assert_eq!(
+            examples(
+                "<pre><code>a &lt;<em>b</em>&gt; &amp;lt;</code></pre>\
+                <pre><code>a &lt;b&gt; &amp;lt;</code></pre>"
+            ),
+            ["a <b> &lt;"]
+        );
+        assert!(examples(&page("No examples here")).is_empty());
+    }
+
+    /// Serves a single request with `body`, returning the raw request.
+    pub(crate) fn mock_server(
+        body: String,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        mock_server_with_status("200 OK", body)
+    }
+
+    pub(crate) fn mock_server_with_status(
+        status: &'static str,
+        body: String,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn submit() {
+        let (url, server) = mock_server(page("That's not the right answer."));
+        let client = Client::with_base_url("session=abc\n", &url);
+
+        let outcome = client.submit(5, Part::Two, &Answer::from(46_u64));
+        let request = server.join().unwrap();
+
+        assert_eq!(outcome, Ok(Outcome::Wrong));
+        assert!(request.starts_with("POST /2023/day/5/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=46"));
+    }
+
+    #[test]
+    fn fetch_errors() {
+        let fetch = |status, body: &[u8]| {
+            let body = String::from_utf8_lossy(body).to_string();
+            let (url, server) = mock_server_with_status(status, body);
+            let result =
+                Client::with_base_url("session=abc", &url).fetch_input(3);
+            server.join().unwrap();
+            result
+        };
+
+        assert!(matches!(
+            fetch("404 Not Found", b"Please don't repeatedly request"),
+            Err(InputError::NotUnlocked { day: 3 })
+        ));
+        assert!(matches!(
+            fetch("400 Bad Request", b"Puzzle inputs differ by user."),
+            Err(InputError::Http {
+                day: 3,
+                status: 400
+            })
+        ));
+        assert_eq!(fetch("200 OK", b"467..114..\n").unwrap(), "467..114..\n");
+    }
+
+    #[test]
+    fn transport_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        assert!(matches!(
+            Client::with_base_url("session=abc", &url).fetch_input(3),
+            Err(InputError::Transport { day: 3, .. })
+        ));
+    }
+}
@@ -1,3 +1,18 @@
+mod cancel;
+pub use cancel::{cancelled, CancelToken};
+
+mod executor;
+pub use executor::Executor;
+
+mod grid;
+pub use grid::{Grid, Point};
+
+mod progress;
+pub use progress::Progress;
+
+mod range;
+pub use range::{RangeMap, RangeSet};
+
 use std::fmt::Debug;
 use std::iter::{Product, Sum};
 use std::ops::{Add, Sub, Mul, Div};
@@ -30,7 +45,7 @@ impl BoundedInt for u8 {
     }
 
     fn max_value() -> Self {
-        std::u8::MAX
+        u8::MAX
     }
 }
 
@@ -40,7 +55,7 @@ impl BoundedInt for u16 {
     }
 
     fn max_value() -> Self {
-        std::u16::MAX
+        u16::MAX
     }
 }
 
@@ -50,7 +65,7 @@ impl BoundedInt for u32 {
     }
 
     fn max_value() -> Self {
-        std::u32::MAX
+        u32::MAX
     }
 }
 
@@ -60,7 +75,7 @@ impl BoundedInt for u64 {
     }
 
     fn max_value() -> Self {
-        std::u64::MAX
+        u64::MAX
     }
 }
 
@@ -70,7 +85,7 @@ impl BoundedInt for u128 {
     }
 
     fn max_value() -> Self {
-        std::u128::MAX
+        u128::MAX
     }
 }
 
@@ -80,56 +95,56 @@ impl BoundedInt for usize {
     }
 
     fn max_value() -> Self {
-        std::usize::MAX
+        usize::MAX
     }
 }
 
 impl BoundedInt for i8 {
     fn min_value() -> Self {
-        std::i8::MIN
+        i8::MIN
     }
 
     fn max_value() -> Self {
-        std::i8::MAX
+        i8::MAX
     }
 }
 
 impl BoundedInt for i16 {
     fn min_value() -> Self {
-        std::i16::MIN
+        i16::MIN
     }
 
     fn max_value() -> Self {
-        std::i16::MAX
+        i16::MAX
     }
 }
 
 impl BoundedInt for i32 {
     fn min_value() -> Self {
-        std::i32::MIN
+        i32::MIN
     }
 
     fn max_value() -> Self {
-        std::i32::MAX
+        i32::MAX
     }
 }
 
 impl BoundedInt for i64 {
     fn min_value() -> Self {
-        std::i64::MIN
+        i64::MIN
     }
 
     fn max_value() -> Self {
-        std::i64::MAX
+        i64::MAX
     }
 }
 
 impl BoundedInt for i128 {
     fn min_value() -> Self {
-        std::i128::MIN
+        i128::MIN
     }
 
     fn max_value() -> Self {
-        std::i128::MAX
+        i128::MAX
     }
 }
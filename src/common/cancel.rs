@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+thread_local! {
+    static CURRENT: RefCell<CancelToken> = RefCell::new(CancelToken::new());
+}
+
+/// A flag asking running work to stop early. Work checks the token of the
+/// thread it runs on, so that solutions don't need to pass one around.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// The token of the current thread, which is never cancelled unless
+    /// another one was set with [`CancelToken::set_current`].
+    pub fn current() -> Self {
+        CURRENT.with(|token| token.borrow().clone())
+    }
+
+    pub fn set_current(token: Self) {
+        CURRENT.with(|current| *current.borrow_mut() = token);
+    }
+}
+
+/// Whether the work running on this thread has been asked to stop.
+pub fn cancelled() -> bool {
+    CURRENT.with(|token| token.borrow().is_cancelled())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_token() {
+        assert!(!cancelled());
+
+        let token = CancelToken::new();
+        CancelToken::set_current(token.clone());
+        assert!(!cancelled());
+        token.cancel();
+        assert!(cancelled());
+        assert!(CancelToken::current().is_cancelled());
+
+        let other_thread = std::thread::spawn(cancelled).join().unwrap();
+        assert!(!other_thread);
+    }
+}
@@ -0,0 +1,221 @@
+use super::CancelToken;
+use crate::logging::Span;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Spreads work items over a pool of threads in batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Executor {
+    pub threads: usize,
+    /// How many items a worker takes at a time.
+    pub batch_size: usize,
+}
+
+impl Default for Executor {
+    /// One thread per core, taking 1000 items at a time.
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, usize::from),
+            batch_size: 1000,
+        }
+    }
+}
+
+impl Executor {
+    /// Applies `map` to every item and combines the results with `reduce`,
+    /// or returns `None` if there are no items.
+    ///
+    /// `reduce` only needs to be associative: results are combined in the
+    /// order of their items. A panic in any worker stops the others and is
+    /// resumed on the calling thread. Workers also stop once the calling
+    /// thread's [`CancelToken`] is cancelled, leaving the result partial.
+    pub fn map_reduce<I, T, M, R>(
+        &self,
+        items: I,
+        map: M,
+        reduce: R,
+    ) -> Option<T>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: Send,
+        T: Send,
+        M: Fn(I::Item) -> T + Sync,
+        R: Fn(T, T) -> T + Sync,
+    {
+        self.map_reduce_with(items, map, reduce, |_, _| ())
+    }
+
+    /// Like [`Executor::map_reduce`], also calling `on_batch` with the
+    /// number of items in each finished batch and its reduced result, such
+    /// as to report [`Progress`](super::Progress).
+    pub fn map_reduce_with<I, T, M, R, B>(
+        &self,
+        items: I,
+        map: M,
+        reduce: R,
+        on_batch: B,
+    ) -> Option<T>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: Send,
+        T: Send,
+        M: Fn(I::Item) -> T + Sync,
+        R: Fn(T, T) -> T + Sync,
+        B: Fn(usize, &T) + Sync,
+    {
+        let items = Mutex::new(items.into_iter().enumerate());
+        let batch_size = self.batch_size.max(1);
+        let stop = AtomicBool::new(false);
+        let token = CancelToken::current();
+        let span = Span::current();
+
+        let work = || {
+            let mut results = Vec::new();
+            while !stop.load(Ordering::Relaxed) && !token.is_cancelled() {
+                let batch: Vec<_> = match items.lock() {
+                    Ok(mut items) => items.by_ref().take(batch_size).collect(),
+                    Err(_) => break,
+                };
+                let Some(&(index, _)) = batch.first() else {
+                    break;
+                };
+                let len = batch.len();
+                let result = batch
+                    .into_iter()
+                    .map(|(_, item)| map(item))
+                    .reduce(&reduce);
+                if let Some(result) = result {
+                    on_batch(len, &result);
+                    results.push((index, result));
+                }
+            }
+            results
+        };
+
+        let outcomes: Vec<_> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.max(1))
+                .map(|i| {
+                    let worker = || {
+                        let _span = span.enter();
+                        panic::catch_unwind(AssertUnwindSafe(work)).inspect_err(
+                            |_| stop.store(true, Ordering::Relaxed),
+                        )
+                    };
+                    thread::Builder::new()
+                        .name(format!("worker-{i}"))
+                        .spawn_scoped(scope, worker)
+                        .expect("could not spawn a worker thread")
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        let mut results = Vec::new();
+        for outcome in outcomes {
+            match outcome {
+                Ok(batches) => results.extend(batches),
+                Err(payload) => panic::resume_unwind(payload),
+            }
+        }
+        results.sort_by_key(|&(index, _)| index);
+        results.into_iter().map(|(_, result)| result).reduce(reduce)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_reduce() {
+        for threads in [1, 3, 8] {
+            for batch_size in [1, 7, 1000] {
+                let executor = Executor {
+                    threads,
+                    batch_size,
+                };
+                assert_eq!(
+                    executor.map_reduce(0..10_000_u64, |n| n * 2, |a, b| a + b),
+                    Some(99_990_000)
+                );
+                assert_eq!(
+                    executor.map_reduce(
+                        (0..50).map(|n| n % 10),
+                        |n| n.to_string(),
+                        |a, b| a + &b
+                    ),
+                    Some("0123456789".repeat(5))
+                );
+            }
+        }
+
+        let none = Executor::default().map_reduce(0..0, |n: u8| n, u8::min);
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn on_batch() {
+        let executor = Executor {
+            threads: 2,
+            batch_size: 3,
+        };
+        let batches = Mutex::new(Vec::new());
+        let sum = executor.map_reduce_with(
+            0..10,
+            |n| n,
+            |a, b| a + b,
+            |len, &sum| batches.lock().unwrap().push((len, sum)),
+        );
+
+        let mut batches = batches.into_inner().unwrap();
+        batches.sort();
+        assert_eq!(sum, Some(45));
+        assert_eq!(batches, [(1, 9), (3, 3), (3, 12), (3, 21)]);
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let token = CancelToken::new();
+        CancelToken::set_current(token.clone());
+
+        let executor = Executor {
+            threads: 2,
+            batch_size: 1,
+        };
+        let count = executor.map_reduce(
+            0..,
+            |n: u64| {
+                if n == 100 {
+                    token.cancel();
+                }
+                1
+            },
+            |a, b| a + b,
+        );
+        assert!(count.unwrap() < 200);
+    }
+
+    #[test]
+    #[should_panic(expected = "no square root of 13")]
+    fn propagates_panics() {
+        Executor {
+            threads: 4,
+            batch_size: 2,
+        }
+        .map_reduce(
+            0..100,
+            |n| match n {
+                13 => panic!("no square root of {n}"),
+                n => n,
+            },
+            u32::max,
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use crate::Input;
+use std::ops::{Index, IndexMut};
+
+/// A cell position, counted from the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Point {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// The point `rows` down and `cols` right of this one, unless that
+    /// would be above or left of the grid.
+    pub fn offset(self, rows: isize, cols: isize) -> Option<Self> {
+        Some(Self {
+            row: self.row.checked_add_signed(rows)?,
+            col: self.col.checked_add_signed(cols)?,
+        })
+    }
+}
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, or `None` if they differ in length.
+    pub fn from_rows<R>(rows: impl IntoIterator<Item = R>) -> Option<Self>
+    where
+        R: IntoIterator<Item = T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for row in rows {
+            let len = cells.len();
+            cells.extend(row);
+            if *width.get_or_insert(cells.len() - len) != cells.len() - len {
+                return None;
+            }
+            height += 1;
+        }
+
+        Some(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// Reads every line of `input` as a row, converting each byte with
+    /// `cell`.
+    pub fn parse(input: &Input, mut cell: impl FnMut(u8) -> T) -> Option<Self> {
+        Self::from_rows(
+            input
+                .grid()
+                .into_iter()
+                .map(|row| row.iter().map(|&b| cell(b)).collect::<Vec<_>>()),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.height && p.col < self.width
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.contains(p)
+            .then(|| &self.cells[p.row * self.width + p.col])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.contains(p) {
+            true => Some(&mut self.cells[p.row * self.width + p.col]),
+            false => None,
+        }
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| {
+            (0..width).map(move |col| Point::new(row, col))
+        })
+    }
+
+    /// The points above, left, right and below `p` that are in the grid.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(p, &ORTHOGONAL)
+    }
+
+    /// The points around `p`, diagonals included, that are in the grid.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(p, &ADJACENT)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        p: Point,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |&(rows, cols)| p.offset(rows, cols))
+            .filter(|&p| self.contains(p))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// The cells of column `col`, from the top. Panics if the grid is
+    /// narrower than that, like [`Grid::row`] for rows.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            col < self.width,
+            "column {col} is out of bounds for a grid {} wide",
+            self.width
+        );
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// The diagonals running down and to the right, starting from the
+    /// bottom left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = Vec<&T>> {
+        (0..self.width + self.height).skip(1).map(|k| {
+            self.points()
+                .filter(|p| p.col + self.height == p.row + k)
+                .map(|p| &self[p])
+                .collect()
+        })
+    }
+
+    /// The diagonals running down and to the left, starting from the top
+    /// left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Vec<&T>> {
+        (0..(self.width + self.height).saturating_sub(1)).map(|k| {
+            self.points()
+                .filter(|p| p.row + p.col == k)
+                .map(|p| &self[p])
+                .collect()
+        })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The grid mirrored along its main diagonal, so that rows become
+    /// columns.
+    pub fn transpose(&self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flat_map(|col| col.cloned()).collect(),
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        let mut rotated = self.transpose();
+        rotated
+            .cells
+            .chunks_mut(self.height.max(1))
+            .for_each(<[T]>::reverse);
+        rotated
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let mut rotated = self.transpose();
+        rotated.cells.reverse();
+        rotated
+            .cells
+            .chunks_mut(self.height.max(1))
+            .for_each(<[T]>::reverse);
+        rotated
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!("{p:?} is outside a {}x{} grid", self.width, self.height)
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p).unwrap_or_else(|| {
+            panic!("{p:?} is outside a {width}x{height} grid")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(&Input::from("abc\ndef"), char::from).unwrap()
+    }
+
+    fn text(grid: &Grid<char>) -> Vec<String> {
+        grid.rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(1, 2)], 'f');
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+        assert!(Grid::from_rows([vec![1, 2], vec![3]]).is_none());
+
+        let mut grid = grid;
+        grid[Point::new(0, 0)] = 'z';
+        assert_eq!(text(&grid), ["zbc", "def"]);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = grid();
+        let at = |p| grid[p];
+
+        assert_eq!(
+            grid.neighbours4(Point::new(0, 0))
+                .map(at)
+                .collect::<String>(),
+            "bd"
+        );
+        assert_eq!(
+            grid.neighbours4(Point::new(1, 1))
+                .map(at)
+                .collect::<String>(),
+            "bdf"
+        );
+        assert_eq!(
+            grid.neighbours8(Point::new(0, 1))
+                .map(at)
+                .collect::<String>(),
+            "acdef"
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let grid = grid();
+        let collect = |cells: Vec<&char>| cells.into_iter().collect::<String>();
+
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.columns().map(|c| c.collect()).collect::<Vec<String>>(),
+            ["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.diagonals().map(collect).collect::<Vec<_>>(),
+            ["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals().map(collect).collect::<Vec<_>>(),
+            ["a", "bd", "ce", "f"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is out of bounds for a grid 3 wide")]
+    fn column_out_of_bounds() {
+        let _ = grid().column(3);
+    }
+
+    #[test]
+    fn transform() {
+        let grid = grid();
+        assert_eq!(text(&grid.transpose()), ["ad", "be", "cf"]);
+        assert_eq!(text(&grid.rotate_clockwise()), ["da", "eb", "fc"]);
+        assert_eq!(text(&grid.rotate_counterclockwise()), ["cf", "be", "ad"]);
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn on a terminal.
+const TTY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often a progress line is logged when stderr isn't a terminal.
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Work done so far on a long-running computation, shown as a line on
+/// stderr that redraws itself when it is a terminal and as periodic log
+/// lines otherwise, which are shown at any log level. Can be updated from
+/// any number of threads.
+pub struct Progress {
+    label: String,
+    total: u64,
+    done: AtomicU64,
+    /// The smallest result found so far, or `u64::MAX` before the first.
+    best: AtomicU64,
+    last_report: Mutex<Instant>,
+    tty: bool,
+}
+
+impl Progress {
+    /// Starts tracking `total` units of work.
+    pub fn new(label: impl Into<String>, total: u64) -> Self {
+        Self {
+            label: label.into(),
+            total,
+            done: AtomicU64::new(0),
+            best: AtomicU64::new(u64::MAX),
+            last_report: Mutex::new(Instant::now()),
+            tty: atty::is(atty::Stream::Stderr),
+        }
+    }
+
+    /// Records `n` more units of work as done.
+    pub fn advance(&self, n: u64) {
+        self.done.fetch_add(n, Ordering::Relaxed);
+        self.report();
+    }
+
+    /// Records a result, which is the best so far if it is the smallest.
+    pub fn set_best(&self, result: u64) {
+        self.best.fetch_min(result, Ordering::Relaxed);
+    }
+
+    pub fn line(&self) -> String {
+        let done = self.done.load(Ordering::Relaxed);
+        let percent = match self.total {
+            0 => 100.0,
+            total => done as f64 / total as f64 * 100.0,
+        };
+
+        let mut line =
+            format!("{}: {done}/{} ({percent:.1}%)", self.label, self.total);
+        match self.best.load(Ordering::Relaxed) {
+            u64::MAX => (),
+            best => line.push_str(&format!(", best so far {best}")),
+        }
+        line
+    }
+
+    fn report(&self) {
+        let interval = if self.tty { TTY_INTERVAL } else { LOG_INTERVAL };
+        // Another thread reporting at the same time is reason enough to skip.
+        let Ok(mut last_report) = self.last_report.try_lock() else {
+            return;
+        };
+        if last_report.elapsed() < interval {
+            return;
+        }
+        *last_report = Instant::now();
+
+        if self.tty {
+            eprint!("\r\x1b[2K{}", self.line());
+        } else {
+            log::info!("{}", self.line());
+        }
+    }
+}
+
+impl Drop for Progress {
+    /// Clears the progress line so that it doesn't mix with later output.
+    fn drop(&mut self) {
+        if self.tty {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line() {
+        let progress = Progress::new("day 5 part 2", 400);
+        assert_eq!(progress.line(), "day 5 part 2: 0/400 (0.0%)");
+
+        progress.advance(150);
+        progress.advance(1);
+        progress.set_best(35);
+        progress.set_best(40);
+        assert_eq!(
+            progress.line(),
+            "day 5 part 2: 151/400 (37.8%), best so far 35"
+        );
+        assert_eq!(Progress::new("empty", 0).line(), "empty: 0/0 (100.0%)");
+    }
+}
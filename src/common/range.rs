@@ -0,0 +1,409 @@
+use super::BoundedInt;
+use std::ops::Range;
+
+/// A set of values stored as sorted, disjoint, non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: BoundedInt> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: BoundedInt> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges: Vec<Range<T>> =
+            iter.into_iter().filter(|r| r.start < r.end).collect();
+        ranges.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+        Self { ranges: merged }
+    }
+}
+
+impl<T: BoundedInt> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::from_iter([range])
+    }
+}
+
+impl<T: BoundedInt> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.ranges.iter().cloned().chain([range]).collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j))
+        {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values of `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        for a in &self.ranges {
+            let mut start = a.start;
+            for b in &other.ranges {
+                if b.end <= start {
+                    continue;
+                }
+                if b.start >= a.end {
+                    break;
+                }
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+                start = b.end;
+            }
+            if start < a.end {
+                ranges.push(start..a.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Splits the set into the values below `value` and the rest.
+    pub fn split_at(&self, value: T) -> (Self, Self) {
+        let mut below = Vec::new();
+        let mut rest = Vec::new();
+        for r in &self.ranges {
+            if r.end <= value {
+                below.push(r.clone());
+            } else if r.start >= value {
+                rest.push(r.clone());
+            } else {
+                below.push(r.start..value);
+                rest.push(value..r.end);
+            }
+        }
+        (Self { ranges: below }, Self { ranges: rest })
+    }
+
+    /// Moves every value by the distance from `from` to `to`.
+    pub fn shift(&self, from: T, to: T) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| shift(r.start, from, to)..shift(r.end, from, to))
+                .collect(),
+        }
+    }
+}
+
+fn shift<T: BoundedInt>(value: T, from: T, to: T) -> T {
+    if to >= from {
+        value + (to - from)
+    } else {
+        value - (from - to)
+    }
+}
+
+/// A piecewise linear map, where each source range is moved to start at its
+/// destination and values outside every source map to themselves. Sources
+/// are expected not to overlap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// `(source, destination start)` pairs sorted by source.
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: BoundedInt> Default for RangeMap<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T: BoundedInt> FromIterator<(Range<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(iter: I) -> Self {
+        let mut entries: Vec<_> = iter
+            .into_iter()
+            .filter(|(source, _)| source.start < source.end)
+            .collect();
+        entries.sort_by_key(|(source, _)| source.start);
+        Self { entries }
+    }
+}
+
+impl<T: BoundedInt> RangeMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `source` to the range of the same length starting at
+    /// `destination`.
+    pub fn insert(&mut self, source: Range<T>, destination: T) {
+        if source.start < source.end {
+            let i = self
+                .entries
+                .partition_point(|(s, _)| s.start < source.start);
+            self.entries.insert(i, (source, destination));
+        }
+    }
+
+    pub fn entries(&self) -> &[(Range<T>, T)] {
+        &self.entries
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let i = self.entries.partition_point(|(s, _)| s.end <= value);
+        match self.entries.get(i) {
+            Some((source, destination)) if source.start <= value => {
+                shift(value, source.start, *destination)
+            }
+            _ => value,
+        }
+    }
+
+    /// Maps every value of `set` at once.
+    pub fn map(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut mapped = Vec::new();
+        for (source, destination) in &self.entries {
+            let overlap = set.intersection(&RangeSet::from(source.clone()));
+            mapped.extend(overlap.shift(source.start, *destination).ranges);
+        }
+
+        let sources: RangeSet<T> = self
+            .entries
+            .iter()
+            .map(|(source, _)| source.clone())
+            .collect();
+        mapped
+            .into_iter()
+            .chain(set.difference(&sources).ranges)
+            .collect()
+    }
+
+    /// Maps every value of `range`, returning the resulting ranges.
+    pub fn map_range(&self, range: Range<T>) -> Vec<Range<T>> {
+        self.map(&RangeSet::from(range)).ranges
+    }
+
+    /// Splits `range` into the pieces this map moves as one, each with the
+    /// value its start maps to. Gaps between sources map to themselves.
+    fn pieces(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut pieces = Vec::new();
+        let mut start = range.start;
+        for (source, destination) in &self.entries {
+            let overlap = start.max(source.start)..range.end.min(source.end);
+            if overlap.start >= overlap.end {
+                continue;
+            }
+            if start < overlap.start {
+                pieces.push((start..overlap.start, start));
+            }
+            let to = shift(overlap.start, source.start, *destination);
+            start = overlap.end;
+            pieces.push((overlap, to));
+        }
+        if start < range.end {
+            pieces.push((start..range.end, start));
+        }
+        pieces
+    }
+
+    /// The map applying `self` and then `next`.
+    pub fn compose(&self, next: &Self) -> Self {
+        let mut entries = Vec::new();
+        for (source, destination) in &self.entries {
+            let image =
+                *destination..shift(source.end, source.start, *destination);
+            for (piece, to) in next.pieces(image) {
+                let from = shift(piece.start, *destination, source.start);
+                let end = shift(piece.end, *destination, source.start);
+                entries.push((from..end, to));
+            }
+        }
+
+        let sources: RangeSet<T> = self
+            .entries
+            .iter()
+            .map(|(source, _)| source.clone())
+            .collect();
+        let unmapped =
+            RangeSet::from(T::min_value()..T::max_value()).difference(&sources);
+        for gap in unmapped.ranges {
+            entries.extend(next.pieces(gap));
+        }
+
+        entries
+            .into_iter()
+            .filter(|(source, destination)| source.start != *destination)
+            .collect()
+    }
+
+    /// The map undoing this one, if it is one-to-one: every destination
+    /// range must cover exactly the values its sources leave behind.
+    pub fn invert(&self) -> Option<Self> {
+        let sources: RangeSet<T> = self
+            .entries
+            .iter()
+            .map(|(source, _)| source.clone())
+            .collect();
+        let destinations: Vec<Range<T>> = self
+            .entries
+            .iter()
+            .map(|(source, to)| *to..shift(source.end, source.start, *to))
+            .collect();
+
+        let covered: RangeSet<T> = destinations.iter().cloned().collect();
+        let total = |ranges: &[Range<T>]| {
+            ranges
+                .iter()
+                .fold(T::default(), |n, r| n + (r.end - r.start))
+        };
+        if covered != sources || total(&destinations) != total(&covered.ranges)
+        {
+            return None;
+        }
+
+        Some(
+            destinations
+                .into_iter()
+                .zip(&self.entries)
+                .map(|(destination, (source, _))| (destination, source.start))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<u64>]) -> RangeSet<u64> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn normalise() {
+        assert_eq!(
+            set(&[5..8, 1..3, 2..4, 8..9, 10..10]).ranges(),
+            [1..4, 5..9]
+        );
+        assert!(set(&[3..3]).is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+
+        assert_eq!(a.union(&b).ranges(), [0..40]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25, 28..30]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..28]);
+        assert_eq!(b.difference(&a).ranges(), [10..20, 30..40]);
+        assert!(a.contains(0) && a.contains(29));
+        assert!(!a.contains(10) && !a.contains(30));
+
+        let (below, rest) = a.split_at(25);
+        assert_eq!(
+            (below.ranges(), rest.ranges()),
+            (&[0..10, 20..25][..], &[25..30][..])
+        );
+
+        assert_eq!(a.shift(20, 25).ranges(), [5..15, 25..35]);
+        assert_eq!(set(&[20..30]).shift(20, 15).ranges(), [15..25]);
+    }
+
+    #[test]
+    fn map() {
+        // The seed-to-soil map of day 5's example.
+        let map: RangeMap<u64> =
+            [(98..100, 50), (50..98, 52)].into_iter().collect();
+
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(14), 14);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(100), 100);
+
+        assert_eq!(map.map_range(79..93), [81..95]);
+        assert_eq!(map.map_range(40..60), [40..50, 52..62]);
+        assert_eq!(map.map_range(90..110), [50..52, 92..110]);
+    }
+
+    #[test]
+    fn compose_and_invert() {
+        // The seed-to-soil and soil-to-fertilizer maps of day 5's example.
+        let seed_to_soil: RangeMap<u64> =
+            [(98..100, 50), (50..98, 52)].into_iter().collect();
+        let soil_to_fertilizer: RangeMap<u64> =
+            [(15..52, 0), (52..54, 37), (0..15, 39)]
+                .into_iter()
+                .collect();
+        let seed_to_fertilizer = seed_to_soil.compose(&soil_to_fertilizer);
+
+        for seed in 0..120 {
+            let fertilizer = soil_to_fertilizer.get(seed_to_soil.get(seed));
+            assert_eq!(seed_to_fertilizer.get(seed), fertilizer, "{seed}");
+        }
+        assert_eq!(
+            seed_to_fertilizer.map_range(79..93),
+            soil_to_fertilizer.map_range(81..95)
+        );
+
+        let fertilizer_to_seed = seed_to_fertilizer.invert().unwrap();
+        for seed in 0..120 {
+            let fertilizer = seed_to_fertilizer.get(seed);
+            assert_eq!(fertilizer_to_seed.get(fertilizer), seed);
+        }
+
+        let overlapping: RangeMap<u64> =
+            [(0..10, 5), (10..20, 0)].into_iter().collect();
+        assert_eq!(overlapping.invert(), None);
+        let leaky: RangeMap<u64> = [(0..10, 20)].into_iter().collect();
+        assert_eq!(leaky.invert(), None);
+    }
+}
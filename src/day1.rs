@@ -1,3 +1,6 @@
+use crate::{Answer, Input, Solution};
+use std::error::Error;
+
 fn parse_part1(line: &str) -> Vec<u32> {
     line.chars().filter_map(|c| c.to_digit(10)).collect()
 }
@@ -43,10 +46,59 @@ fn run(input: &[String], parse_fn: fn(&str) -> Vec<u32>) -> u32 {
         .sum::<_>()
 }
 
-pub fn part1(input: &[String]) -> u32 {
-    run(input, parse_part1)
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Vec<String>;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        run(parsed, parse_part1).into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        run(parsed, parse_part2).into()
+    }
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    run(input, parse_part2)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::tests::assert_consistent;
+
+    const EXAMPLE_PART1: &str = "
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const EXAMPLE_PART2: &str = "
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn part1() {
+        let input = Input::from(EXAMPLE_PART1);
+        assert_eq!(Day1::part1(&Day1::parse(&input).unwrap()), 142.into());
+    }
+
+    #[test]
+    fn part2() {
+        let input = Input::from(EXAMPLE_PART2);
+        assert_eq!(Day1::part2(&Day1::parse(&input).unwrap()), 281.into());
+    }
+
+    #[test]
+    fn consistent_inputs() {
+        assert_consistent::<Day1>(EXAMPLE_PART1);
+        assert_consistent::<Day1>(EXAMPLE_PART2);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::common::BoundedInt;
-use std::collections::HashMap;
+use crate::{Answer, Input, Solution};
+use std::error::Error;
 
 #[derive(Debug)]
 struct State<T> {
@@ -30,33 +31,25 @@ impl<T: BoundedInt> Default for State<T> {
 
 impl<T: BoundedInt> State<T>
 {
-    fn from(s: &str) -> Self {
-        let mut color_count: HashMap<&str, T> = HashMap::from([
-            ("blue", T::min_value()),
-            ("red", T::min_value()),
-            ("green", T::min_value()),
-        ]);
-
-        s.split(',').map(|i| i.trim()).for_each(|split| {
-            let split_on_space: Vec<&str> = split.split(' ').collect();
-            let count: T = split_on_space
-                .first()
-                .expect("No count found")
-                .parse()
-                .unwrap_or(T::min_value());
-            let color: &str = split_on_space.last().expect("No color found");
-            color_count.insert(color, count);
-        });
-
-        let get_count = |color: &str| -> T {
-            *color_count.get(color).unwrap_or(&T::min_value())
-        };
-
-        Self {
-            n_blue: get_count("blue"),
-            n_red: get_count("red"),
-            n_green: get_count("green"),
+    fn from(s: &str) -> Result<Self, Box<dyn Error>> {
+        let mut state = Self::default();
+        for cubes in s.split(',').map(|i| i.trim()) {
+            let invalid = || {
+                format!(
+                    "invalid cubes {cubes:?} -- expected `N blue`, `N red` \
+                    or `N green`"
+                )
+            };
+            let (count, color) = cubes.split_once(' ').ok_or_else(invalid)?;
+            let count: T = count.parse().map_err(|_| invalid())?;
+            match color {
+                "blue" => state.n_blue = count,
+                "red" => state.n_red = count,
+                "green" => state.n_green = count,
+                _ => return Err(invalid().into()),
+            }
         }
+        Ok(state)
     }
 
     fn power(&self) -> T {
@@ -65,16 +58,16 @@ impl<T: BoundedInt> State<T>
 }
 
 #[derive(Debug)]
-struct Game<T> {
+pub struct Game<T> {
     id: T,
     iterations: Vec<State<T>>,
 }
 
 impl<T: std::fmt::Display> std::fmt::Display for Game<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Game {}", self.id)?;
-        for (i, state) in self.iterations.iter().enumerate() {
-            writeln!(f, "Iteration {i}: {state}")?;
+        write!(f, "game {}", self.id)?;
+        for state in &self.iterations {
+            write!(f, "; {state}")?;
         }
         Ok(())
     }
@@ -82,28 +75,25 @@ impl<T: std::fmt::Display> std::fmt::Display for Game<T> {
 
 impl<T: BoundedInt> Game<T>
 {
-    fn from(s: &str) -> Game<T> {
-        let split_on_colon: Vec<&str> = s.split(':').collect();
-        let game_id: T = split_on_colon
-            .first()
-            .expect("Incorrect input format -- no colon")
+    fn from(s: &str) -> Result<Game<T>, Box<dyn Error>> {
+        let (game, iterations) = s.split_once(':').ok_or_else(|| {
+            format!("invalid game {s:?} -- expected `Game N: ...`")
+        })?;
+        let game_id: T = game
             .split(' ')
-            .last()
-            .expect("Incorrect input format -- no space")
-            .parse()
-            .unwrap_or(T::min_value());
-
-        let iterations: Vec<State<_>> = split_on_colon
-            .last()
-            .expect("Incorrect input format -- no colon")
+            .next_back()
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| format!("invalid game ID in {s:?}"))?;
+
+        let iterations = iterations
             .split(';')
             .map(State::<_>::from)
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        Self {
+        Ok(Self {
             id: game_id,
             iterations,
-        }
+        })
     }
 
     fn is_valid(&self, constraint: &State<T>) -> bool {
@@ -125,27 +115,90 @@ impl<T: BoundedInt> Game<T>
     }
 }
 
-pub fn part1(input: &[String]) -> u32 {
-    let constraint = State {
-        n_blue: 14,
-        n_red: 12,
-        n_green: 13,
-    };
-
-    input
-        .iter()
-        .inspect(|line| log::debug!("Parsing line:\n{line}"))
-        .map(|line| Game::<u16>::from(line))
-        .inspect(|game| log::debug!("Parsed game:\n{game}"))
-        .filter(|game| game.is_valid(&constraint))
-        .fold(0, |acc, game| acc + game.id) as u32
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Parsed = Vec<Game<u16>>;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>> {
+        input
+            .lines()
+            .map(|line| {
+                log::trace!("parsing {line:?}");
+                let game = Game::from(line)?;
+                log::debug!("parsed {game}");
+                Ok(game)
+            })
+            .collect()
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        let constraint = State {
+            n_blue: 14,
+            n_red: 12,
+            n_green: 13,
+        };
+
+        parsed
+            .iter()
+            .filter(|game| game.is_valid(&constraint))
+            .fold(0_u32, |acc, game| acc + game.id as u32)
+            .into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        parsed
+            .iter()
+            .map(|game| game.min_valid_set())
+            .inspect(|state| log::debug!("minimum viable state {state}"))
+            .fold(0_u32, |acc, i| acc + i.power() as u32)
+            .into()
+    }
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    input
-        .iter()
-        .inspect(|line| log::debug!("Parsing line:\n{line}"))
-        .map(|line| Game::<u16>::from(line).min_valid_set())
-        .inspect(|state| log::debug!("Min viable state:\n{state}"))
-        .fold(0, |acc, i| acc + i.power()) as u32
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::tests::assert_consistent;
+
+    const EXAMPLE: &str = "
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn part1() {
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day2::part1(&Day2::parse(&input).unwrap()), 8.into());
+    }
+
+    #[test]
+    fn part2() {
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day2::part2(&Day2::parse(&input).unwrap()), 2286.into());
+    }
+
+    #[test]
+    fn invalid_games() {
+        let parse = |line: &str| Day2::parse(&Input::from(line)).err();
+        assert_eq!(
+            parse("Game 1: 3 blue, x red").map(|e| e.to_string()),
+            Some(
+                "invalid cubes \"x red\" -- expected `N blue`, `N red` or \
+                `N green`"
+                    .to_string()
+            )
+        );
+        assert!(parse("Game 1: 3 blue; 2 purple").is_some());
+        assert!(parse("Game 1: 3 blue;").is_some());
+        assert!(parse("Game x: 3 blue").is_some());
+        assert!(parse("Game 1 3 blue").is_some());
+    }
+
+    #[test]
+    fn consistent_inputs() {
+        assert_consistent::<Day2>(EXAMPLE);
+    }
 }
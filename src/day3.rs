@@ -1,201 +1,137 @@
-use crate::common::BoundedInt;
-use std::collections::HashMap;
-use std::slice::Iter;
+use crate::common::{BoundedInt, Grid, Point};
+use crate::{Answer, Input, Solution};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
 
-#[derive(Debug)]
-struct Node<T> {
-    symbol: char,
-    children: Vec<T>,
-}
-
-#[derive(Debug, Hash, Eq, PartialEq)]
-struct Point {
-    row_i: usize,
-    col_i: usize,
-}
-
-struct Grid<T> {
-    nodes: HashMap<Point, Node<T>>,
-}
+/// The characters that mark an engine part.
+const SYMBOLS: &[u8] = b"*+&=$@/-%#";
 
 #[derive(Debug)]
-enum Token<T> {
-    Number(T),
-    Symbol(char),
+struct PartNumber<T> {
+    value: T,
+    /// Every symbol the number touches, diagonals included.
+    symbols: BTreeSet<Point>,
 }
 
-fn read_next_token<T: BoundedInt>(s: &mut Iter<char>) -> (Token<T>, usize) {
-    let parsed_num: Result<T, _> = str::parse(
-        &s.clone()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>(),
-    );
-    match parsed_num {
-        Ok(n) => {
-            let n_digits = n.checked_log10().unwrap_or(0) + 1;
-            (0..n_digits).for_each(|_| {
-                s.next();
-            });
-            (Token::Number(n), n_digits)
-        }
-        Err(_) => (Token::Symbol(*s.next().unwrap()), 1),
-    }
+pub struct Schematic<T> {
+    grid: Grid<u8>,
+    numbers: Vec<PartNumber<T>>,
 }
 
-impl<T: BoundedInt> Grid<T> {
-    fn from(input: &[String]) -> Self {
-        let input: Vec<Vec<char>> =
-            input.iter().map(|s| s.chars().collect()).collect();
-
-        let (tx, rx) = std::sync::mpsc::channel();
-        let producer = std::thread::spawn(move || {
-            input.iter().enumerate().for_each(|(row_i, row)| {
-                let mut col_i = 0;
-                let mut s = row.iter();
-                while col_i < row.len() {
-                    let (token, n_digits) = read_next_token(&mut s);
-                    log::debug!(
-                        "Next token: {:?} ({:?} digits)",
-                        token,
-                        n_digits
-                    );
-                    if let Token::Number(n) = token {
-                        if let Some((symbol, pt)) = Self::neighboring_symbol(
-                            &input,
-                            row_i,
-                            col_i,
-                            col_i + n_digits,
-                        ) {
-                            log::debug!(
-                                "Found symbol {:?} at {:?}",
-                                symbol,
-                                pt
-                            );
-                            tx.send((pt, symbol, n)).unwrap();
-                        }
-                    }
-
-                    col_i += n_digits;
+impl<T: BoundedInt> Schematic<T> {
+    fn from(input: &Input) -> Option<Self> {
+        let grid = Grid::parse(input, |b| b)?;
+
+        let mut numbers = Vec::new();
+        for (row_i, row) in grid.rows().enumerate() {
+            let mut col_i = 0;
+            while col_i < row.len() {
+                let n_digits = row[col_i..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                if n_digits == 0 {
+                    col_i += 1;
+                    continue;
                 }
-            })
-        });
-
-        let mut nodes: HashMap<Point, Node<T>> = HashMap::new();
-        rx.iter().for_each(|(pt, symbol, n)| {
-            nodes
-                .entry(pt)
-                .or_insert(Node {
-                    symbol,
-                    children: Vec::new(),
-                })
-                .children
-                .push(n);
-        });
-        producer.join().unwrap();
-
-        Self { nodes }
-    }
 
-    fn neighboring_symbol(
-        input: &[Vec<char>],
-        row_i: usize,
-        col_i: usize,
-        col_end: usize,
-    ) -> Option<(char, Point)> {
-        let symbol = |c: &char| {
-            let c: char = *c;
-            match c {
-                '*' | '+' | '&' | '=' | '$' | '@' | '/' | '-' | '%' | '#' => {
-                    Some(c)
-                }
-                _ => None,
+                let digits = &row[col_i..col_i + n_digits];
+                let value = std::str::from_utf8(digits).ok()?.parse().ok()?;
+                let symbols = (col_i..col_i + n_digits)
+                    .flat_map(|col_i| {
+                        grid.neighbours8(Point::new(row_i, col_i))
+                    })
+                    .filter(|&p| SYMBOLS.contains(&grid[p]))
+                    .collect();
+                let number = PartNumber { value, symbols };
+                log::debug!("found {number:?}");
+                numbers.push(number);
+
+                col_i += n_digits;
             }
-        };
-
-        let check = |row_i: usize, col_i: usize| {
-            symbol(input.get(row_i)?.get(col_i)?)
-                .map(|c| (c, Point { row_i, col_i }))
-        };
-
-        check(row_i, col_i.saturating_sub(1))
-            .or(check(row_i, col_end))
-            .or((col_i.saturating_sub(1)..=col_end).fold(None, |acc, i| {
-                acc.or(check(row_i.saturating_sub(1), i))
-                    .or(check(row_i.saturating_add(1), i))
-            }))
+        }
+
+        Some(Self { grid, numbers })
     }
 
     fn sum_non_orphans(&self) -> T {
-        self.nodes
-            .values()
-            .inspect(|n| log::debug!("{}: {:?}", n.symbol, n.children))
-            .map(|n| n.children.iter().fold(T::default(), |acc, n| acc + *n))
+        self.numbers
+            .iter()
+            .filter(|n| !n.symbols.is_empty())
+            .map(|n| n.value)
             .sum()
     }
 
     fn sum_and_multiply_non_orphans(&self) -> T {
-        self.nodes
+        let mut gears: HashMap<Point, Vec<T>> = HashMap::new();
+        for number in &self.numbers {
+            for &p in &number.symbols {
+                if self.grid[p] == b'*' {
+                    gears.entry(p).or_default().push(number.value);
+                }
+            }
+        }
+
+        gears
             .values()
-            .filter(|node| node.symbol == '*' && node.children.len() == 2)
-            .inspect(|node| log::debug!("{}: {:?}", node.symbol, node.children))
-            .map(|node| node.children.clone().into_iter().product())
+            .filter(|children| children.len() == 2)
+            .inspect(|children| log::debug!("gear between {children:?}"))
+            .map(|children| children.iter().copied().product())
             .sum()
     }
 }
 
-pub fn part1(input: &[String]) -> u32 {
-    let grid = Grid::from(input);
-    grid.sum_non_orphans()
-}
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = Schematic<u64>;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>> {
+        Ok(Schematic::from(input).ok_or("invalid schematic")?)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        parsed.sum_non_orphans().into()
+    }
 
-pub fn part2(input: &[String]) -> u32 {
-    let grid = Grid::from(input);
-    grid.sum_and_multiply_non_orphans()
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        parsed.sum_and_multiply_non_orphans().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::solution::tests::assert_consistent;
+
+    const EXAMPLE: &str = "
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
     #[test]
     fn part1() {
-        env_logger::builder()
-            .format_target(false)
-            .format_level(false)
-            .format_timestamp(None)
-            .init();
-
-        let input = [
-            "467..114..",
-            "...*......",
-            "..35..633.",
-            "......#...",
-            "617*......",
-            ".....+.58.",
-            "..592.....",
-            "......755.",
-            "...$.*....",
-            ".664.598..",
-        ]
-        .map(|l| l.to_string());
-
-        assert_eq!(super::part1(&input), 4361);
+        crate::logging::init_for_tests();
+
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day3::part1(&Day3::parse(&input).unwrap()), 4361.into());
     }
 
     #[test]
     fn part2() {
-        let input = [
-            "467..114..",
-            "...*......",
-            "..35..633.",
-            "......#...",
-            "617*......",
-            ".....+.58.",
-            "..592.....",
-            "......755.",
-            "...$.*....",
-            ".664.598..",
-        ]
-        .map(|l| l.to_string());
-
-        assert_eq!(super::part2(&input), 467835);
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day3::part2(&Day3::parse(&input).unwrap()), 467835.into());
+    }
+
+    #[test]
+    fn consistent_inputs() {
+        assert_consistent::<Day3>(EXAMPLE);
     }
 }
@@ -1,40 +1,38 @@
+use crate::{Answer, Input, Solution};
 use std::collections::HashSet;
+use std::error::Error;
 
 #[derive(Clone, Debug)]
-struct Card {
+pub struct Card {
     _id: usize,
     winners: HashSet<u32>,
     values: HashSet<u32>,
 }
 
 impl Card {
-    fn from(s: &str) -> Self {
-        let to_set = |s: &str| {
-            s.split(' ')
-                .filter(|s| !s.is_empty())
-                .map(|x| x.parse().expect("invalid number"))
-                .collect()
+    fn from(s: &str) -> Result<Self, Box<dyn Error>> {
+        let invalid = || {
+            format!("invalid card {s:?} -- expected `Card N: 1 2 3 | 4 5 6`")
         };
-        let split_on_colon: Vec<_> = s.split(':').collect();
-        let id = split_on_colon
-            .first()
-            .expect("Invalid format -- 'Card 1:' is expected")
+        let to_set = |numbers: &str| {
+            numbers
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| invalid()))
+                .collect::<Result<_, _>>()
+        };
+        let (card, game) = s.split_once(':').ok_or_else(invalid)?;
+        let id = card
             .split(' ')
-            .last()
-            .expect("Invalid format -- 'Card 1:' is expected")
-            .parse()
-            .expect("Invalid format -- ID must be a number");
-        let game: Vec<_> = split_on_colon
-            .last()
-            .expect("Invalid format -- 'Card 1: 1 2 3 4' is expected")
-            .split('|')
-            .collect();
-
-        Self {
+            .next_back()
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(invalid)?;
+        let (winners, values) = game.split_once('|').ok_or_else(invalid)?;
+
+        Ok(Self {
             _id: id,
-            winners: to_set(game[0]),
-            values: to_set(game[1]),
-        }
+            winners: to_set(winners)?,
+            values: to_set(values)?,
+        })
     }
 
     fn winners(&self) -> usize {
@@ -51,59 +49,94 @@ impl Card {
     }
 }
 
-pub fn part1(input: &[String]) -> u32 {
-    input
-        .iter()
-        .map(|s| Card::from(s))
-        .inspect(|c| log::debug!("{:?}", c))
-        .map(|c| c.value())
-        .inspect(|c| log::debug!("{:?}", c))
-        .sum()
-}
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Card>;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>> {
+        input
+            .lines()
+            .map(|line| {
+                let card = Card::from(line)?;
+                log::debug!("parsed {card:?}");
+                Ok(card)
+            })
+            .collect()
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        parsed
+            .iter()
+            .map(|c| c.value())
+            .inspect(|value| log::debug!("card worth {value}"))
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        let mut counts = vec![1; parsed.len()];
+        parsed
+            .iter()
+            .map(|c| c.winners())
+            .enumerate()
+            .for_each(|(i, n)| {
+                let count = counts[i];
+                // Wins never copy cards past the end of the table.
+                let last = (i + n).min(parsed.len() - 1);
+                counts[i + 1..=last].iter_mut().for_each(|c| *c += count);
+            });
 
-pub fn part2(input: &[String]) -> u32 {
-    let mut counts = vec![1; input.len()];
-    input
-        .iter()
-        .map(|c| Card::from(c).winners())
-        .enumerate()
-        .for_each(|(i, n)| {
-            let count = counts[i];
-            counts[i + 1..=i + n].iter_mut().for_each(|c| *c += count);
-        });
-
-    counts.iter().sum()
+        counts.iter().sum::<u32>().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::solution::tests::assert_consistent;
+
+    const EXAMPLE: &str = "
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
     #[test]
     fn part1() {
-        let input = [
-            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
-            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
-            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
-            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
-            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
-            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        ]
-        .map(|s| s.to_string());
-
-        assert_eq!(super::part1(&input), 13);
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day4::part1(&Day4::parse(&input).unwrap()), 13.into());
     }
 
     #[test]
     fn part2() {
-        let input = [
-            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
-            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
-            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
-            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
-            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
-            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
-        ]
-        .map(|s| s.to_string());
-
-        assert_eq!(super::part2(&input), 30);
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day4::part2(&Day4::parse(&input).unwrap()), 30.into());
+
+        let input = Input::from("Card 1: 1 2 | 1 3\nCard 2: 1 2 | 1 2");
+        assert_eq!(Day4::part2(&Day4::parse(&input).unwrap()), 3.into());
+    }
+
+    #[test]
+    fn invalid_cards() {
+        let parse = |line: &str| Day4::parse(&Input::from(line)).err();
+        assert_eq!(
+            parse("Card 1: 1 2 3").map(|e| e.to_string()),
+            Some(
+                "invalid card \"Card 1: 1 2 3\" -- expected \
+                `Card N: 1 2 3 | 4 5 6`"
+                    .to_string()
+            )
+        );
+        assert!(parse("Card 1: 1 x | 2").is_some());
+        assert!(parse("Card x: 1 | 2").is_some());
+        assert!(parse("1 2 | 3").is_some());
+    }
+
+    #[test]
+    fn consistent_inputs() {
+        assert_consistent::<Day4>(EXAMPLE);
     }
 }
@@ -1,263 +1,428 @@
-use crate::common::BoundedInt;
-use std::collections::{BTreeMap, HashMap};
+use crate::common::{BoundedInt, RangeMap, RangeSet};
+use crate::solution::Part;
+use crate::{Answer, Input, Solution};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::ops::Range;
-use std::sync::Arc;
+
+/// What is wrong with an almanac. Blocks and lines are numbered from 1,
+/// lines counting from the start of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlmanacError {
+    Syntax {
+        block: usize,
+        line: usize,
+        reason: String,
+    },
+    /// A range whose end doesn't fit in the number type.
+    Overflow {
+        block: usize,
+        line: usize,
+    },
+    /// Two ranges of one map share source values.
+    Overlap {
+        block: usize,
+        lines: [usize; 2],
+    },
+    DuplicateMap {
+        from: String,
+    },
+    /// The chain of maps from `seed` stops before reaching `location`.
+    MissingMap {
+        from: String,
+    },
+    /// The chain of maps from `seed` comes back to a category it has
+    /// already been through.
+    Cycle {
+        category: String,
+    },
+    /// A map the chain from `seed` never reaches.
+    Unused {
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::Syntax {
+                block,
+                line,
+                reason,
+            } => write!(f, "block {block}, line {line}: {reason}"),
+            AlmanacError::Overflow { block, line } => write!(
+                f,
+                "block {block}, line {line}: range overflows the number type"
+            ),
+            AlmanacError::Overlap {
+                block,
+                lines: [a, b],
+            } => write!(
+                f,
+                "block {block}: source ranges on lines {a} and {b} overlap"
+            ),
+            AlmanacError::DuplicateMap { from } => {
+                write!(f, "more than one map from `{from}`")
+            }
+            AlmanacError::MissingMap { from } => {
+                write!(f, "no map from `{from}` on the way to `location`")
+            }
+            AlmanacError::Cycle { category } => {
+                write!(f, "the maps lead back to `{category}`")
+            }
+            AlmanacError::Unused { from, to } => {
+                write!(
+                    f,
+                    "the `{from}-to-{to}` map is never reached from `seed`"
+                )
+            }
+        }
+    }
+}
+
+impl Error for AlmanacError {}
+
+/// One blank-line separated block of the input, with its position.
+struct Block<'a> {
+    number: usize,
+    first_line: usize,
+    text: &'a str,
+}
+
+impl<'a> Block<'a> {
+    /// Numbered lines of the block.
+    fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let first_line = self.first_line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(i, l)| (first_line + i, l))
+    }
+
+    fn syntax(&self, line: usize, reason: impl Into<String>) -> AlmanacError {
+        AlmanacError::Syntax {
+            block: self.number,
+            line,
+            reason: reason.into(),
+        }
+    }
+
+    /// Reads a line of whitespace separated numbers.
+    fn numbers<T: BoundedInt>(
+        &self,
+        line: usize,
+        text: &str,
+    ) -> Result<Vec<T>, AlmanacError> {
+        text.split_whitespace()
+            .map(|n| {
+                n.parse().map_err(|_| {
+                    self.syntax(line, format!("invalid number {n:?}"))
+                })
+            })
+            .collect()
+    }
+}
 
 #[derive(Debug, Clone)]
 struct Map<T> {
     from: String,
     to: String,
-    from_ranges: BTreeMap<T, Range<T>>,
-    to_ranges: Vec<Range<T>>,
+    ranges: RangeMap<T>,
 }
 
 impl<T: BoundedInt> Map<T> {
-    fn from(s: &str) -> Option<Self> {
-        let split_on_colon: Vec<_> = s.split(':').collect();
-
-        let label: Vec<_> = split_on_colon
-            .first()?
-            .trim()
-            .split("map")
-            .collect::<Vec<_>>()
-            .first()?
-            .trim()
-            .split("-to-")
-            .collect();
-
-        let from = label.first()?.trim().to_string();
-        let to = label.last()?.trim().to_string();
-
-        let mut ranges = split_on_colon.last()?.trim().split('\n');
-
-        let mut from_ranges = Vec::new();
-        let mut to_ranges = Vec::new();
-        ranges.try_for_each(|r| {
-            let mut vals: Vec<T> = r
-                .split(' ')
-                .map(|v| v.parse())
-                .collect::<Result<_, _>>()
-                .ok()?;
-            let range = vals.pop()?;
-            let from_begin = vals.pop()?;
-            let to_begin = vals.pop()?;
-
-            from_ranges.push(from_begin..(from_begin + range));
-            to_ranges.push(to_begin..(to_begin + range));
-            Some(())
-        });
+    /// Reads an `X-to-Y map:` header followed by `destination source
+    /// length` lines.
+    fn parse(block: &Block) -> Result<Self, AlmanacError> {
+        let mut lines = block.lines();
+        let (line, header) = lines
+            .next()
+            .ok_or_else(|| block.syntax(block.first_line, "empty block"))?;
+        let (from, to) = header
+            .strip_suffix(" map:")
+            .and_then(|label| label.split_once("-to-"))
+            .ok_or_else(|| {
+                block.syntax(
+                    line,
+                    format!("expected `X-to-Y map:`, found {header:?}"),
+                )
+            })?;
+
+        let mut entries = Vec::new();
+        for (line, text) in lines {
+            let [to_begin, from_begin, range] = block.numbers(line, text)?[..]
+            else {
+                return Err(block.syntax(
+                    line,
+                    format!(
+                        "expected `destination source length`, found {text:?}"
+                    ),
+                ));
+            };
+            if from_begin > T::max_value() - range
+                || to_begin > T::max_value() - range
+            {
+                return Err(AlmanacError::Overflow {
+                    block: block.number,
+                    line,
+                });
+            }
+            entries.push((line, from_begin..(from_begin + range), to_begin));
+        }
 
-        let mut zipped: Vec<_> =
-            to_ranges.into_iter().zip(from_ranges.iter()).collect();
-        zipped.sort_by_key(|(_, from)| from.start);
-        let to_ranges = zipped.into_iter().map(|(to, _)| to).collect();
+        entries.sort_by_key(|(_, source, _)| source.start);
+        if let Some(pair) = entries
+            .windows(2)
+            .find(|pair| pair[0].1.end > pair[1].1.start)
+        {
+            let mut lines = [pair[0].0, pair[1].0];
+            lines.sort();
+            return Err(AlmanacError::Overlap {
+                block: block.number,
+                lines,
+            });
+        }
 
-        Some(Self {
-            from,
-            to,
-            from_ranges: from_ranges
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            ranges: entries
                 .into_iter()
-                .map(|r| (r.start, r))
+                .map(|(_, source, destination)| (source, destination))
                 .collect(),
-            to_ranges,
         })
     }
 
     fn next_val(&self, val: T) -> T {
-        match self
-            .from_ranges
-            .range(..=val)
-            .zip(self.to_ranges.iter())
-            .last()
-        {
-            Some(((start, range), to_range)) if range.contains(&val) => {
-                let offset = val - *start;
-                to_range.start + offset
-            }
-            _ => val,
+        self.ranges.get(val)
+    }
+
+    fn next_vals(&self, vals: &RangeSet<T>) -> RangeSet<T> {
+        self.ranges.map(vals)
+    }
+
+    /// The map taking `category` to itself.
+    fn identity(category: &str) -> Self {
+        Self {
+            from: category.to_string(),
+            to: category.to_string(),
+            ranges: RangeMap::new(),
         }
     }
-}
 
-enum SeedList<T> {
-    List(Vec<T>),
-    Intervals(Vec<Range<T>>),
+    /// The map applying `self` and then `next`, if `next` picks up from
+    /// the category `self` maps to.
+    fn compose(&self, next: &Self) -> Option<Self> {
+        (self.to == next.from).then(|| Self {
+            from: self.from.clone(),
+            to: next.to.clone(),
+            ranges: self.ranges.compose(&next.ranges),
+        })
+    }
+
+    /// The map going back from `to` to `from`, if `self` is one-to-one.
+    fn invert(&self) -> Option<Self> {
+        Some(Self {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            ranges: self.ranges.invert()?,
+        })
+    }
 }
 
 type LookupTable<T> = HashMap<String, Map<T>>;
 
-fn get_seed_list_from_groups<T>(groups: &[&str]) -> Option<SeedList<T>>
-where
-    T: BoundedInt,
-{
-    Some(SeedList::List(
-        groups
-            .first()?
-            .trim()
-            .split(':')
-            .last()?
-            .trim()
-            .split(' ')
-            .map(|s| s.parse())
-            .collect::<Result<_, _>>()
-            .ok()?,
-    ))
+pub struct Almanac<T> {
+    seeds: Vec<T>,
+    /// Every map in the chain starting from seeds, composed into one.
+    location: Map<T>,
 }
 
-fn parse_groups<T, F>(
-    seed_list_parse_fn: F,
-    groups: &[&str],
-) -> Option<(SeedList<T>, LookupTable<T>)>
-where
-    T: BoundedInt,
-    F: Fn(&[&str]) -> Option<SeedList<T>>,
-{
-    let seed_list = seed_list_parse_fn(groups)?;
-    let lookup: LookupTable<_> = LookupTable::from_iter(
-        groups
-            .get(1..)?
-            .iter()
-            .filter_map(|g| Map::from(g))
-            .map(|m| (m.from.clone(), m)),
-    );
-    Some((seed_list, lookup))
-}
+impl<T: BoundedInt> Almanac<T> {
+    fn parse(input: &Input) -> Result<Self, AlmanacError> {
+        let mut first_line = 1;
+        let mut blocks = input.blocks().enumerate().map(|(i, text)| {
+            let block = Block {
+                number: i + 1,
+                first_line,
+                text,
+            };
+            first_line += text.lines().count() + 1;
+            block
+        });
 
-pub fn part1(input: &[String]) -> u32 {
-    let input = input.join("\n");
-    let Some((SeedList::List(seed_list), lookup)) = parse_groups::<u64, _>(
-        get_seed_list_from_groups,
-        &input.split("\n\n").collect::<Vec<_>>(),
-    ) else { panic!() };
-
-    seed_list
-        .into_iter()
-        .map(|seed| {
-            let mut next_val = seed;
-            let mut next_type = "seed";
-            while let Some(map) = lookup.get(next_type) {
-                log::debug!("type: {:?}, val: {:?}", next_type, next_val);
-                next_type = &map.to;
-                next_val = map.next_val(next_val);
-            }
-            next_val
-        })
-        .min()
-        .unwrap()
-        .try_into()
-        .unwrap()
-}
+        let block = blocks.next().ok_or(AlmanacError::Syntax {
+            block: 1,
+            line: 1,
+            reason: "empty almanac".to_string(),
+        })?;
+        let (line, text) = block.lines().next().unwrap_or((1, ""));
+        let seeds = text
+            .strip_prefix("seeds:")
+            .ok_or_else(|| block.syntax(line, "expected `seeds:`"))
+            .and_then(|seeds| block.numbers(line, seeds))?;
+        if seeds.is_empty() {
+            return Err(block.syntax(line, "expected at least one seed"));
+        }
+        if block.lines().count() > 1 {
+            return Err(block.syntax(line + 1, "expected a blank line"));
+        }
 
-fn get_seed_ranges_from_groups<T>(groups: &[&str]) -> Option<SeedList<T>>
-where
-    T: BoundedInt,
-{
-    let ranges = groups
-        .first()?
-        .trim()
-        .split(':')
-        .last()?
-        .trim()
-        .split(' ')
-        .filter_map(|s| s.parse::<T>().ok());
-
-    let start_values = ranges.clone().step_by(2);
-    let offset_values = ranges.clone().skip(1).step_by(2);
-    Some(SeedList::Intervals(
-        start_values
-            .zip(offset_values)
-            .map(|(start, offset)| start..(start + offset))
-            .collect(),
-    ))
-}
+        let mut lookup = LookupTable::new();
+        for block in blocks {
+            let map = Map::parse(&block)?;
+            if let Some(map) = lookup.insert(map.from.clone(), map) {
+                return Err(AlmanacError::DuplicateMap { from: map.from });
+            }
+        }
 
-fn distribute_seeds<T, F>(
-    l: Vec<Range<T>>,
-    lookup_table: LookupTable<T>,
-    f: F,
-) -> T
-where
-    T: BoundedInt + Send + Sync + 'static,
-    F: Fn(T, &LookupTable<T>) -> T + Copy + Send + 'static,
-    Range<T>: Iterator<Item = T>,
-{
-    let l = Box::leak(l.into_boxed_slice());
-    let lookup_table = Arc::new(lookup_table);
-
-    let batch_size = 10_000;
-    let (task_tx, task_rx) = crossbeam_channel::bounded(10 * batch_size);
-    let producer = std::thread::spawn(move || {
-        l.iter_mut().for_each(|seed_range| loop {
-            match seed_range.take(batch_size).collect::<Vec<_>>() {
-                v if !v.is_empty() => task_tx.send(v).unwrap(),
-                _ => break,
+        let mut location = Map::identity("seed");
+        let mut visited = vec!["seed".to_string()];
+        while let Some(map) = lookup.remove(&location.to) {
+            log::debug!("composing {} with {}", location.to, map.to);
+            if visited.contains(&map.to) {
+                return Err(AlmanacError::Cycle { category: map.to });
             }
-        });
+            visited.push(map.to.clone());
+            location = location
+                .compose(&map)
+                .expect("maps are looked up by the category they map from");
+        }
 
-        drop(task_tx);
-        log::debug!("producer done");
-    });
-
-    let (tx, rx) = crossbeam_channel::unbounded();
-    let threads: Vec<_> =
-        (0..std::thread::available_parallelism().unwrap().into())
-            .map(|_| {
-                let task_rx = task_rx.clone();
-                let tx = tx.clone();
-                let lookup_table = Arc::clone(&lookup_table);
-                std::thread::spawn(move || {
-                    while let Ok(seeds) = task_rx.recv() {
-                        let local_min = seeds
-                            .into_iter()
-                            .map(|seed| f(seed, &lookup_table))
-                            .min()
-                            .unwrap_or(T::max_value());
-                        tx.send(local_min).unwrap();
-                        log::debug!("sending local min: {:?}", local_min);
-                    }
-                    drop(tx);
-                    log::debug!("worker done");
-                })
+        if location.to != "location" {
+            return Err(AlmanacError::MissingMap { from: location.to });
+        }
+        if let Some(map) =
+            lookup.into_values().min_by(|a, b| a.from.cmp(&b.from))
+        {
+            return Err(AlmanacError::Unused {
+                from: map.from,
+                to: map.to,
+            });
+        }
+
+        Ok(Self { seeds, location })
+    }
+
+    /// The seed planted at `location`, if the maps are one-to-one.
+    pub fn seed(&self, location: T) -> Option<T> {
+        Some(self.location.invert()?.next_val(location))
+    }
+
+    /// Reads the seed list, the first line of the almanac, as
+    /// `start length` pairs. None of the ranges may be empty, so that there
+    /// is always a seed to plant.
+    fn seed_ranges(&self) -> Result<Vec<Range<T>>, AlmanacError> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(AlmanacError::Syntax {
+                block: 1,
+                line: 1,
+                reason: "expected pairs of seeds".to_string(),
+            });
+        }
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| {
+                if pair[1] == T::min_value() {
+                    return Err(AlmanacError::Syntax {
+                        block: 1,
+                        line: 1,
+                        reason: "expected seed ranges of at least one seed"
+                            .to_string(),
+                    });
+                }
+                match pair[0] > T::max_value() - pair[1] {
+                    true => Err(AlmanacError::Overflow { block: 1, line: 1 }),
+                    false => Ok(pair[0]..(pair[0] + pair[1])),
+                }
             })
-            .collect();
-    drop(tx);
+            .collect()
+    }
+}
 
-    let smallest_loc = rx.iter().min().unwrap();
-    producer.join().unwrap();
-    threads.into_iter().for_each(|t| t.join().unwrap());
-    smallest_loc
+fn seed_location<T: BoundedInt>(seed: T, location: &Map<T>) -> T {
+    location.next_val(seed)
 }
 
-pub fn part2(input: &[String]) -> u32 {
-    let input = input.join("\n");
-    let Some(
-        (SeedList::Intervals(seed_list), lookup)
-    ) = parse_groups::<u64, _>(
-        get_seed_ranges_from_groups,
-        &input.split("\n\n").collect::<Vec<_>>(),
-    ) else { panic!() };
-
-    let process_seed = |seed, lookup: &LookupTable<_>| {
-        let mut next_val = seed;
-        let mut next_type = "seed";
-        while let Some(map) = lookup.get(next_type) {
-            log::debug!("type: {}, val: {}", next_type, next_val);
-            next_type = &map.to;
-            next_val = map.next_val(next_val);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = Almanac<u64>;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>> {
+        Ok(Almanac::parse(input)?)
+    }
+
+    /// Part 2 reads the seeds as pairs, part 1 as they are.
+    fn validate(
+        parsed: &Self::Parsed,
+        part: Part,
+    ) -> Result<(), Box<dyn Error>> {
+        if part == Part::Two {
+            parsed.seed_ranges()?;
         }
-        next_val
-    };
+        Ok(())
+    }
 
-    distribute_seeds(seed_list, lookup, process_seed)
-        .try_into()
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        parsed
+            .seeds
+            .iter()
+            .map(|&seed| seed_location(seed, &parsed.location))
+            .min()
+            .unwrap()
+            .into()
+    }
+
+    /// Pushes whole ranges of seeds through the maps at once, splitting
+    /// them wherever a map's source ranges begin or end.
+    fn part2(parsed: &Self::Parsed) -> Answer {
+        let seeds = parsed
+            .seed_ranges()
+            .expect("checked by validate")
+            .into_iter()
+            .collect();
+        parsed.location.next_vals(&seeds).min().unwrap().into()
+    }
+}
+
+/// Part 2 by running every single seed through the maps, spread across all
+/// cores. Takes minutes on a real input, so it reports its progress.
+#[cfg(feature = "brute-force")]
+pub fn brute_force_part2(parsed: &Almanac<u64>) -> Answer {
+    use crate::common::{Executor, Progress};
+
+    let seed_ranges = parsed.seed_ranges().expect("checked by validate");
+    let total = seed_ranges.iter().map(|r| r.end - r.start).sum();
+    let progress = Progress::new("day 5 part 2 (brute force)", total);
+
+    let executor = Executor {
+        batch_size: 10_000,
+        ..Default::default()
+    };
+    executor
+        .map_reduce_with(
+            seed_ranges.into_iter().flatten(),
+            |seed| seed_location(seed, &parsed.location),
+            u64::min,
+            |seeds, &location| {
+                progress.set_best(location);
+                progress.advance(seeds as u64);
+            },
+        )
         .unwrap()
+        .into()
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn part1() {
-        let input = "
+    use super::*;
+    use crate::solution::tests::assert_consistent;
+    use crate::solution::{run, Failure};
+
+    const EXAMPLE: &str = "
 seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -290,56 +455,184 @@ temperature-to-humidity map:
 
 humidity-to-location map:
 60 56 37
-56 93 4"
-            .trim()
-            .lines()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
+56 93 4";
 
-        assert_eq!(super::part1(&input), 35);
+    #[test]
+    fn part1() {
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day5::part1(&Day5::parse(&input).unwrap()), 35.into());
     }
 
     #[test]
     fn part2() {
-        let input = "
-seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
+        let input = Input::from(EXAMPLE);
+        assert_eq!(Day5::part2(&Day5::parse(&input).unwrap()), 46.into());
+    }
 
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
+    /// Compares part 2 with the brute force on the example, and on the
+    /// cached input too when `AOC_SLOW` is set.
+    #[cfg(feature = "brute-force")]
+    #[test]
+    fn brute_force_agrees() {
+        let mut inputs = vec![Input::from(EXAMPLE)];
+        if std::env::var_os("AOC_SLOW").is_some() {
+            let cache = crate::input::Cache::from_env();
+            inputs.extend(cache.get(5).unwrap().map(Input::from));
+        }
 
-water-to-light map:
-88 18 7
-18 25 70
+        for input in inputs {
+            let parsed = Day5::parse(&input).unwrap();
+            assert_eq!(brute_force_part2(&parsed), Day5::part2(&parsed));
+        }
+    }
 
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+    #[test]
+    fn composed_maps() {
+        let almanac = Day5::parse(&Input::from(EXAMPLE)).unwrap();
+        assert_eq!(
+            (almanac.location.from.as_str(), almanac.location.to.as_str()),
+            ("seed", "location")
+        );
+
+        for (seed, location) in [(79, 82), (14, 43), (55, 86), (13, 35)] {
+            assert_eq!(seed_location(seed, &almanac.location), location);
+            assert_eq!(almanac.seed(location), Some(seed));
+        }
+        assert!(Map::<u64>::identity("soil")
+            .compose(&almanac.location)
+            .is_none());
+    }
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+    #[test]
+    fn invalid_almanacs() {
+        let parse = |text: &str| Almanac::<u8>::parse(&Input::from(text)).err();
+
+        assert_eq!(
+            parse("seeds: 1 2\n\nseed-to-location map:\n5 1"),
+            Some(AlmanacError::Syntax {
+                block: 2,
+                line: 4,
+                reason: "expected `destination source length`, found \"5 1\""
+                    .to_string()
+            })
+        );
+        assert_eq!(
+            parse("seeds:\n\nseed-to-location map:\n0 0 1"),
+            Some(AlmanacError::Syntax {
+                block: 1,
+                line: 1,
+                reason: "expected at least one seed".to_string()
+            })
+        );
+        assert!(matches!(
+            parse("seeds: 1 x"),
+            Some(AlmanacError::Syntax {
+                block: 1,
+                line: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse("seeds: 1 2\n\nseed-to-location:\n1 2 3"),
+            Some(AlmanacError::Syntax {
+                block: 2,
+                line: 3,
+                ..
+            })
+        ));
+        assert_eq!(
+            parse("seeds: 1 2\n\nseed-to-location map:\n0 250 10"),
+            Some(AlmanacError::Overflow { block: 2, line: 4 })
+        );
+        assert_eq!(
+            parse("seeds: 1 2\n\nseed-to-location map:\n0 10 5\n1 2 3\n20 8 4"),
+            Some(AlmanacError::Overlap {
+                block: 2,
+                lines: [4, 6]
+            })
+        );
+        assert_eq!(
+            parse(
+                "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\n\
+                seed-to-location map:\n1 2 3"
+            ),
+            Some(AlmanacError::DuplicateMap {
+                from: "seed".to_string()
+            })
+        );
+        assert_eq!(
+            parse(
+                "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\n\
+                water-to-location map:\n1 2 3"
+            ),
+            Some(AlmanacError::MissingMap {
+                from: "soil".to_string()
+            })
+        );
+        assert_eq!(
+            parse(
+                "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\n\
+                soil-to-seed map:\n1 2 3"
+            ),
+            Some(AlmanacError::Cycle {
+                category: "seed".to_string()
+            })
+        );
+        assert_eq!(
+            parse(
+                "seeds: 1 2\n\nseed-to-location map:\n1 2 3\n\n\
+                water-to-light map:\n1 2 3"
+            ),
+            Some(AlmanacError::Unused {
+                from: "water".to_string(),
+                to: "light".to_string()
+            })
+        );
+    }
 
-humidity-to-location map:
-60 56 37
-56 93 4"
-            .trim()
-            .lines()
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
+    #[test]
+    fn seed_pairs() {
+        let almanac = |seeds: &str| {
+            let text =
+                format!("seeds: {seeds}\n\nseed-to-location map:\n0 0 1");
+            Almanac::<u8>::parse(&Input::from(text)).unwrap()
+        };
+        assert_eq!(
+            almanac("1 2 250 5").seed_ranges(),
+            Ok(vec![1..3, 250..255])
+        );
+        assert!(matches!(
+            almanac("1 2 3").seed_ranges(),
+            Err(AlmanacError::Syntax {
+                block: 1,
+                line: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            almanac("1 2 3 0").seed_ranges(),
+            Err(AlmanacError::Syntax { .. })
+        ));
+        assert_eq!(
+            almanac("250 10").seed_ranges(),
+            Err(AlmanacError::Overflow { block: 1, line: 1 })
+        );
+
+        let input = Input::from("seeds: 1 2 3\n\nseed-to-location map:\n0 0 1");
+        assert_eq!(
+            run::<Day5>(&input, Part::One, None).unwrap().answer,
+            1.into()
+        );
+        assert_eq!(
+            run::<Day5>(&input, Part::Two, None).unwrap_err(),
+            Failure::Invalid(
+                "block 1, line 1: expected pairs of seeds".to_string()
+            )
+        );
+    }
 
-        assert_eq!(super::part2(&input), 46);
+    #[test]
+    fn consistent_inputs() {
+        assert_consistent::<Day5>(EXAMPLE);
     }
 }
@@ -0,0 +1,464 @@
+use crate::client::{self, Client};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Why a puzzle input couldn't be read or fetched.
+#[derive(Debug)]
+pub enum InputError {
+    /// Neither `AOC_SESSION` nor a `.session` file holds a session cookie.
+    MissingSession,
+    /// The day's puzzle hasn't unlocked yet.
+    NotUnlocked { day: u8 },
+    /// The website answered with an error status.
+    Http { day: u8, status: u16 },
+    /// The website couldn't be reached at all.
+    Transport { day: u8, message: String },
+    /// The website answered with something other than a puzzle input.
+    NotAnInput { day: u8, reason: String },
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The input, read from `origin`, isn't UTF-8.
+    InvalidUtf8 { origin: String },
+}
+
+impl InputError {
+    pub fn io(path: &Path, source: std::io::Error) -> Self {
+        match source.kind() {
+            std::io::ErrorKind::InvalidData => InputError::InvalidUtf8 {
+                origin: path.display().to_string(),
+            },
+            _ => InputError::Io {
+                path: path.to_path_buf(),
+                source,
+            },
+        }
+    }
+
+    /// The process exit code to report the error with, following the BSD
+    /// `sysexits.h` conventions.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InputError::MissingSession => 78,
+            InputError::NotUnlocked { .. } => 75,
+            InputError::Http { .. } | InputError::Transport { .. } => 69,
+            InputError::NotAnInput { .. } | InputError::InvalidUtf8 { .. } => {
+                65
+            }
+            InputError::Io { .. } => 74,
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::MissingSession => write!(
+                f,
+                "no session cookie found -- set AOC_SESSION or write it to a \
+                .session file, copying the `session` cookie from a browser \
+                logged in to adventofcode.com"
+            ),
+            InputError::NotUnlocked { day } => {
+                write!(f, "day {day} hasn't unlocked yet")
+            }
+            InputError::Http { day, status } => {
+                write!(f, "fetching day {day} failed with HTTP {status}")?;
+                if matches!(status, 400 | 401 | 403 | 500) {
+                    write!(f, " -- the session cookie may have expired")?;
+                }
+                Ok(())
+            }
+            InputError::Transport { day, message } => write!(
+                f,
+                "could not reach the website to fetch day {day}: {message}"
+            ),
+            InputError::NotAnInput { day, reason } => {
+                write!(f, "day {day}: {reason}, not caching it")
+            }
+            InputError::Io { path, source } => {
+                write!(f, "could not access {}: {source}", path.display())
+            }
+            InputError::InvalidUtf8 { origin } => {
+                write!(f, "the input from {origin} is not valid UTF-8")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Unlock time of day 1, midnight EST on December 1st, 2023.
+const FIRST_UNLOCK_SECS: u64 = 1_701_406_800;
+
+/// Days whose puzzles have unlocked as of `now`.
+pub fn released_days(now: SystemTime) -> Vec<u8> {
+    (1..=25)
+        .filter(|&day| {
+            let unlock = Duration::from_secs(
+                FIRST_UNLOCK_SECS + (day as u64 - 1) * 24 * 60 * 60,
+            );
+            SystemTime::UNIX_EPOCH + unlock <= now
+        })
+        .collect()
+}
+
+/// Rejects responses that are an HTML page or an error message rather than
+/// a puzzle input, which the website sends with a successful status when
+/// the session is missing or the day hasn't unlocked.
+pub fn validate(body: &str) -> Result<(), String> {
+    let start = body.trim_start().to_ascii_lowercase();
+    if start.starts_with("<!doctype") || start.starts_with("<html") {
+        return Err("received an HTML page instead of an input".to_string());
+    }
+
+    let messages = [
+        "Puzzle inputs differ by user",
+        "Please don't repeatedly request this endpoint",
+        "404 Not Found",
+    ];
+    match messages.iter().find(|message| body.contains(*message)) {
+        Some(_) => Err(format!(
+            "received an error instead of an input: {}",
+            body.trim()
+        )),
+        None if body.trim().is_empty() => {
+            Err("received an empty input".to_string())
+        }
+        None => Ok(()),
+    }
+}
+
+/// A puzzle input, read once and then viewed in whichever shape a day
+/// needs. However it was read, the text is normalised to `\n` line endings
+/// with no trailing whitespace on any line and no blank lines around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    /// Reads all of `reader`, such as stdin, as one input.
+    pub fn read(mut reader: impl Read) -> std::io::Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Ok(Self::from(text))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
+
+    /// The groups of lines separated by blank lines.
+    pub fn blocks(&self) -> std::str::Split<'_, &str> {
+        self.text.split("\n\n")
+    }
+
+    /// The lines as rows of bytes, for inputs laid out as a grid.
+    pub fn grid(&self) -> Vec<&[u8]> {
+        self.lines().map(str::as_bytes).collect()
+    }
+
+    /// A 64-bit FNV-1a hash of the normalised text.
+    pub fn checksum(&self) -> Checksum {
+        let hash = self.text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x100_0000_01b3)
+        });
+        Checksum(hash)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        let start = lines.iter().position(|line| !line.is_empty());
+        let end = lines.iter().rposition(|line| !line.is_empty());
+
+        let text = match (start, end) {
+            (Some(start), Some(end)) => lines[start..=end].join("\n"),
+            _ => String::new(),
+        };
+        Self { text }
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+/// Identifies an [`Input`], telling apart the inputs of different accounts
+/// however their line endings or whitespace differ. Written as 16 hex
+/// digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checksum(u64);
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = s.len() == 16 && s.bytes().all(|b| b.is_ascii_hexdigit());
+        match u64::from_str_radix(s, 16) {
+            Ok(hash) if valid => Ok(Self(hash)),
+            _ => {
+                Err(format!("invalid checksum {s:?} -- expected 16 hex digits"))
+            }
+        }
+    }
+}
+
+/// Puzzle inputs downloaded from the website, kept as `dayN` files in one
+/// directory alongside the `dayN.example`, `dayN.example2`, ... examples
+/// taken from the puzzle descriptions.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The directory in `AOC_CACHE_DIR`, or `inputs/` in the crate root so
+    /// that the cache doesn't depend on the working directory.
+    pub fn from_env() -> Self {
+        match std::env::var_os("AOC_CACHE_DIR") {
+            Some(dir) => Self::new(dir),
+            None => Self::new(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs")),
+        }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day}"))
+    }
+
+    /// The path of the `k`th example of `day`, counting from 1.
+    pub fn example_path(&self, day: u8, k: u8) -> PathBuf {
+        match k {
+            1 => self.dir.join(format!("day{day}.example")),
+            k => self.dir.join(format!("day{day}.example{k}")),
+        }
+    }
+
+    /// The cached input for `day`, if there is one.
+    pub fn get(&self, day: u8) -> Result<Option<String>, InputError> {
+        let path = self.input_path(day);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(InputError::io(&path, e)),
+        }
+    }
+
+    /// Downloads the input for `day` and caches it, replacing any cached
+    /// copy. Nothing is cached unless the response looks like an input.
+    pub fn fetch(
+        &self,
+        day: u8,
+        client: &Client,
+    ) -> Result<String, InputError> {
+        if !released_days(SystemTime::now()).contains(&day) {
+            return Err(InputError::NotUnlocked { day });
+        }
+
+        let contents = client.fetch_input(day)?;
+        validate(&contents)
+            .map_err(|reason| InputError::NotAnInput { day, reason })?;
+
+        self.write(&self.input_path(day), &contents)?;
+        Ok(contents)
+    }
+
+    /// Downloads the puzzle description of `day` and caches every example
+    /// in it, returning the paths written.
+    pub fn fetch_examples(
+        &self,
+        day: u8,
+        client: &Client,
+    ) -> Result<Vec<PathBuf>, InputError> {
+        if !released_days(SystemTime::now()).contains(&day) {
+            return Err(InputError::NotUnlocked { day });
+        }
+
+        let examples = client::examples(&client.fetch_puzzle(day)?);
+        if examples.is_empty() {
+            return Err(InputError::NotAnInput {
+                day,
+                reason: "found no examples in the puzzle description"
+                    .to_string(),
+            });
+        }
+
+        (1..)
+            .zip(&examples)
+            .map(|(k, example)| {
+                let path = self.example_path(day, k);
+                self.write(&path, example).map(|_| path)
+            })
+            .collect()
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> Result<(), InputError> {
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(path, contents))
+            .map_err(|e| InputError::io(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::mock_server;
+
+    #[test]
+    fn released() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(released_days(at(FIRST_UNLOCK_SECS - 1)), vec![]);
+        assert_eq!(released_days(at(FIRST_UNLOCK_SECS)), vec![1]);
+        assert_eq!(
+            released_days(at(FIRST_UNLOCK_SECS + 4 * 24 * 60 * 60 + 1)),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(released_days(SystemTime::now()).len(), 25);
+    }
+
+    #[test]
+    fn views() {
+        let input =
+            Input::from("\nseeds: 79 14\n\nsoil:\n1 2\n3 4\n\n".to_string());
+        assert_eq!(input.as_str(), "seeds: 79 14\n\nsoil:\n1 2\n3 4");
+        assert_eq!(input.lines().count(), 5);
+        assert_eq!(
+            input.blocks().collect::<Vec<_>>(),
+            ["seeds: 79 14", "soil:\n1 2\n3 4"]
+        );
+        assert_eq!(
+            Input::from("#.\n.#").grid(),
+            [b"#.".as_slice(), b".#".as_slice()]
+        );
+        assert_eq!(
+            Input::read("1abc2\ntreb7uchet\n".as_bytes()).unwrap(),
+            Input::from("1abc2\ntreb7uchet")
+        );
+    }
+
+    #[test]
+    fn checksums() {
+        let checksum = Input::from("abc").checksum();
+        assert_eq!(checksum.to_string(), "e71fa2190541574b");
+        assert_eq!(checksum.to_string().parse(), Ok(checksum));
+        assert_eq!(Input::from("abc\r\n\n").checksum(), checksum);
+        assert_ne!(Input::from("abd").checksum(), checksum);
+
+        assert!("e71fa219".parse::<Checksum>().is_err());
+        assert!("+71fa2190541574b".parse::<Checksum>().is_err());
+    }
+
+    #[test]
+    fn normalise() {
+        let expected = "  467..114..\n...*......\n\n..35..633.";
+        for text in [
+            "  467..114..\n...*......\n\n..35..633.",
+            "\n\n  467..114..\n...*......\n\n..35..633.\n\n",
+            "  467..114..\r\n...*......\r\n\r\n..35..633.\r\n",
+            "  467..114.. \n...*......\t\n   \n..35..633.\n  \n",
+        ] {
+            assert_eq!(Input::from(text).as_str(), expected, "{text:?}");
+        }
+        assert_eq!(Input::from("\r\n \n").as_str(), "");
+    }
+
+    #[test]
+    fn validate_responses() {
+        assert!(validate("1abc2\npqr3stu8vwx\n").is_ok());
+        assert!(validate("<!DOCTYPE html>\n<html lang=\"en-us\">").is_err());
+        assert!(validate("  <html><body>").is_err());
+        assert!(validate(
+            "Puzzle inputs differ by user.  Please log in to get your puzzle \
+            input.\n"
+        )
+        .is_err());
+        assert!(validate(
+            "Please don't repeatedly request this endpoint before it \
+            unlocks! The calendar countdown is synchronized with the server \
+            time; the link will be enabled on the calendar the instant this \
+            puzzle becomes available.\n"
+        )
+        .is_err());
+        assert!(validate("\n").is_err());
+    }
+
+    #[test]
+    fn fetch_caches_only_inputs() {
+        let dir = std::env::temp_dir().join("aoc-cache-fetch");
+        let cache = Cache::new(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (url, server) = mock_server("<!DOCTYPE html>".to_string());
+        let client = Client::with_base_url("session=abc", &url);
+        assert!(matches!(
+            cache.fetch(2, &client),
+            Err(InputError::NotAnInput { day: 2, .. })
+        ));
+        server.join().unwrap();
+        assert_eq!(cache.get(2).unwrap(), None);
+
+        let (url, server) = mock_server("Game 1: 3 blue\n".to_string());
+        let client = Client::with_base_url("session=abc", &url);
+        assert_eq!(cache.fetch(2, &client).unwrap(), "Game 1: 3 blue\n");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/2/input HTTP/1.1\r\n"));
+        assert_eq!(cache.get(2).unwrap().as_deref(), Some("Game 1: 3 blue\n"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_examples() {
+        let dir = std::env::temp_dir().join("aoc-cache-fetch-examples");
+        let cache = Cache::new(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let page = include_str!("../fixtures/day1.html").to_string();
+        let (url, server) = mock_server(page);
+        let client = Client::with_base_url("session=abc", &url);
+        let paths = cache.fetch_examples(1, &client).unwrap();
+        let request = server.join().unwrap();
+
+        assert!(request.starts_with("GET /2023/day/1 HTTP/1.1\r\n"));
+        assert_eq!(
+            paths,
+            [dir.join("day1.example"), dir.join("day1.example2")]
+        );
+        assert_eq!(
+            std::fs::read_to_string(cache.example_path(1, 2)).unwrap(),
+            "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n\
+            4nineeightseven2\nzoneight234\n7pqrstsixteen\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
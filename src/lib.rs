@@ -1,11 +1,26 @@
-mod common;
+pub mod common;
 
-pub mod day1;
+pub mod answer;
+pub use answer::Answer;
 
-pub mod day2;
+pub mod answers;
 
-pub mod day3;
+pub mod bench;
 
-pub mod day4;
+pub mod client;
 
-pub mod day5;
+pub mod input;
+pub use input::Input;
+
+pub mod logging;
+
+pub mod profile;
+
+pub mod report;
+
+pub mod select;
+
+pub mod solution;
+pub use solution::Solution;
+
+runner::days!();
@@ -0,0 +1,383 @@
+//! Logging tagged with what each thread is working on.
+//!
+//! Every record carries the current [`Span`] and the name of the thread
+//! logging it. Targets are shortened to the module within the crate, so
+//! that `RUST_LOG=day5=debug` enables day 5 alone, and records can also be
+//! written to a file as one JSON object per line.
+
+use crate::report::json_string;
+use crate::solution::Part;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::cell::Cell;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::SystemTime;
+
+/// The level records are written to the JSON log file at, whatever the
+/// level on stderr.
+const JSON_LEVEL: LevelFilter = LevelFilter::Debug;
+
+thread_local! {
+    static CURRENT: Cell<Span> = Cell::new(Span::default());
+}
+
+/// What a thread is working on. Fields left unset when entering a span are
+/// inherited from the enclosing one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub stage: Option<&'static str>,
+}
+
+impl Span {
+    pub fn day(day: u8) -> Self {
+        Self {
+            day: Some(day),
+            ..Self::default()
+        }
+    }
+
+    pub fn part(part: Part) -> Self {
+        Self {
+            part: Some(part),
+            ..Self::default()
+        }
+    }
+
+    pub fn stage(stage: &'static str) -> Self {
+        Self {
+            stage: Some(stage),
+            ..Self::default()
+        }
+    }
+
+    /// The span of the current thread. Threads start outside of any span,
+    /// so work handed to another thread needs to enter it there again.
+    pub fn current() -> Self {
+        CURRENT.with(Cell::get)
+    }
+
+    /// Makes this span current until the returned guard is dropped.
+    pub fn enter(self) -> SpanGuard {
+        let previous = Self::current();
+        CURRENT.with(|current| {
+            current.set(Self {
+                day: self.day.or(previous.day),
+                part: self.part.or(previous.part),
+                stage: self.stage.or(previous.stage),
+            })
+        });
+        SpanGuard { previous }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(day) = self.day {
+            fields.push(("day", day.to_string()));
+        }
+        if let Some(part) = self.part {
+            fields.push(("part", part.to_string()));
+        }
+        if let Some(stage) = self.stage {
+            fields.push(("stage", stage.to_string()));
+        }
+        fields
+    }
+}
+
+/// Restores the previously current [`Span`] when dropped.
+#[must_use]
+pub struct SpanGuard {
+    previous: Span,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.previous));
+    }
+}
+
+/// Strips the crate name off `target`, leaving e.g. `day5` or
+/// `common::executor`.
+fn short_target(target: &str) -> &str {
+    target
+        .strip_prefix(env!("CARGO_CRATE_NAME"))
+        .and_then(|target| target.strip_prefix("::"))
+        .unwrap_or(target)
+}
+
+fn thread_name() -> String {
+    let thread = thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+/// Formats `record`, logged at `time` from within `span`, as one line of
+/// JSON.
+fn json_line(record: &Record, span: Span, time: SystemTime) -> String {
+    let time = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let mut line = format!(
+        "{{\"time\":{time:.6},\"level\":\"{}\",\"target\":{}",
+        record.level(),
+        json_string(record.target())
+    );
+    if let Some(day) = span.day {
+        write!(line, ",\"day\":{day}").unwrap();
+    }
+    if let Some(part) = span.part {
+        write!(line, ",\"part\":{part}").unwrap();
+    }
+    if let Some(stage) = span.stage {
+        write!(line, ",\"stage\":{}", json_string(stage)).unwrap();
+    }
+    write!(
+        line,
+        ",\"thread\":{},\"message\":{}}}",
+        json_string(&thread_name()),
+        json_string(&record.args().to_string())
+    )
+    .unwrap();
+    line
+}
+
+struct Logger {
+    stderr: env_logger::Logger,
+    json: Option<Mutex<File>>,
+}
+
+impl Logger {
+    fn new(builder: &mut env_logger::Builder, json: Option<File>) -> Self {
+        Self {
+            stderr: builder.build(),
+            json: json.map(Mutex::new),
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        match self.json {
+            Some(_) => self.stderr.filter().max(JSON_LEVEL),
+            None => self.stderr.filter(),
+        }
+    }
+
+    fn to_json(&self, metadata: &Metadata) -> bool {
+        self.json.is_some() && metadata.level() <= JSON_LEVEL
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.to_json(metadata)
+            || self.stderr.enabled(
+                &Metadata::builder()
+                    .level(metadata.level())
+                    .target(short_target(metadata.target()))
+                    .build(),
+            )
+    }
+
+    fn log(&self, record: &Record) {
+        let record = Record::builder()
+            .args(*record.args())
+            .level(record.level())
+            .target(short_target(record.target()))
+            .module_path(record.module_path())
+            .file(record.file())
+            .line(record.line())
+            .build();
+        let stderr = self.stderr.matches(&record);
+        if stderr {
+            self.stderr.log(&record);
+        }
+        if !stderr && !self.to_json(record.metadata()) {
+            return;
+        }
+        if let Some(json) = &self.json {
+            let line = json_line(&record, Span::current(), SystemTime::now());
+            if let Ok(mut json) = json.lock() {
+                let _ = json.write_all(format!("{line}\n").as_bytes());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Logs to stderr at `level`, or as overridden by `RUST_LOG`, prefixing
+/// every line with the record's target, span and thread. Progress is
+/// logged at info level, so that long runs show it regardless.
+fn builder(level: LevelFilter) -> env_logger::Builder {
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(level)
+        .filter_module("common::progress", level.max(LevelFilter::Info))
+        .parse_default_env()
+        .format(|f, record| {
+            let mut context = record.target().to_string();
+            for (name, value) in Span::current().fields() {
+                write!(context, " {name}={value}").unwrap();
+            }
+            write!(context, " thread={}", thread_name()).unwrap();
+            writeln!(f, "[{} {context}] {}", record.level(), record.args())
+        });
+    builder
+}
+
+fn install(builder: &mut env_logger::Builder, json: Option<File>) {
+    let logger = Logger::new(builder, json);
+    log::set_max_level(logger.max_level());
+    log::set_boxed_logger(Box::new(logger))
+        .expect("the logger is only initialised once");
+}
+
+/// Sets up logging for a run, also appending every record to `json` if
+/// given. The file gets records down to debug level even when stderr only
+/// shows errors.
+pub fn init(level: LevelFilter, json: Option<&Path>) -> io::Result<()> {
+    let json = match json {
+        Some(path) => {
+            Some(File::options().create(true).append(true).open(path)?)
+        }
+        None => None,
+    };
+    install(&mut builder(level), json);
+    Ok(())
+}
+
+/// Sets up logging for tests, capturing it along with their output. Unlike
+/// [`init`], this can be called any number of times.
+pub fn init_for_tests() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        install(builder(LevelFilter::Error).is_test(true), None);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn spans() {
+        assert_eq!(Span::current(), Span::default());
+        {
+            let _day = Span::day(5).enter();
+            let _part = Span::part(Part::Two).enter();
+            {
+                let _stage = Span::stage("parse").enter();
+                assert_eq!(
+                    Span::current(),
+                    Span {
+                        day: Some(5),
+                        part: Some(Part::Two),
+                        stage: Some("parse"),
+                    }
+                );
+            }
+            assert_eq!(Span::current().stage, None);
+            assert_eq!(Span::current().day, Some(5));
+
+            let other_thread = thread::spawn(Span::current).join().unwrap();
+            assert_eq!(other_thread, Span::default());
+        }
+        assert_eq!(Span::current(), Span::default());
+    }
+
+    #[test]
+    fn targets() {
+        assert_eq!(short_target("advent_of_code_2023::day5"), "day5");
+        assert_eq!(
+            short_target("advent_of_code_2023::common::executor"),
+            "common::executor"
+        );
+        assert_eq!(short_target("ureq::unit"), "ureq::unit");
+    }
+
+    #[test]
+    fn json_lines() {
+        let span = Span {
+            day: Some(3),
+            part: Some(Part::One),
+            stage: Some("solve"),
+        };
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1500);
+        let line = thread::Builder::new()
+            .name("worker-0".to_string())
+            .spawn(move || {
+                json_line(
+                    &Record::builder()
+                        .args(format_args!("found \"42\"\n\tat (1, 2)"))
+                        .level(log::Level::Debug)
+                        .target("day3")
+                        .build(),
+                    span,
+                    time,
+                )
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            line,
+            concat!(
+                r#"{"time":1.500000,"level":"DEBUG","target":"day3","#,
+                r#""day":3,"part":1,"stage":"solve","thread":"worker-0","#,
+                r#""message":"found \"42\"\n\tat (1, 2)"}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn progress_level() {
+        let logger = Logger::new(&mut builder(LevelFilter::Error), None);
+        let enabled = |level, target| {
+            logger.enabled(
+                &Metadata::builder().level(level).target(target).build(),
+            )
+        };
+        let progress = "advent_of_code_2023::common::progress";
+        assert!(enabled(log::Level::Info, progress));
+        assert!(!enabled(log::Level::Debug, progress));
+        assert!(!enabled(log::Level::Info, "advent_of_code_2023::day5"));
+        assert_eq!(logger.max_level(), LevelFilter::Info);
+    }
+
+    #[test]
+    fn json_file_level() {
+        let path = std::env::temp_dir().join("aoc-logging-json-level");
+        let logger = Logger::new(
+            &mut builder(LevelFilter::Error),
+            Some(File::create(&path).unwrap()),
+        );
+        assert_eq!(logger.max_level(), LevelFilter::Debug);
+
+        for level in [log::Level::Debug, log::Level::Trace] {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("at {level}"))
+                    .level(level)
+                    .target("day5")
+                    .build(),
+            );
+        }
+        let lines = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains(r#""level":"DEBUG","target":"day5""#));
+        assert!(lines[0].ends_with(r#""message":"at DEBUG"}"#));
+    }
+}
@@ -1,105 +1,833 @@
+use advent_of_code_2023::answers::{Answers, Entry, Verdict};
+use advent_of_code_2023::bench::{self, Measurement};
+use advent_of_code_2023::client::{Client, Outcome};
+use advent_of_code_2023::input::{self, Input, InputError};
+use advent_of_code_2023::logging;
+use advent_of_code_2023::profile::Profile;
+use advent_of_code_2023::report::{self, Format, Row};
+use advent_of_code_2023::solution::{Failure, Part};
+use advent_of_code_2023::{select, Answer, DAYS};
+use clap::builder::RangedU64ValueParser;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use runner::run_puzzle;
-use std::env;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
-fn fetch_input(day: &str) -> String {
-    println!("Fetching input for day {}...", day);
-    if !std::path::Path::new(".session").exists() {
-        panic!("No session file found");
+#[derive(Parser)]
+#[command(about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Read the puzzle input from this file instead of the cache, or from
+    /// stdin if PATH is `-`
+    #[arg(long, global = true, value_name = "PATH")]
+    input: Option<PathBuf>,
+
+    /// Use the Kth example from the puzzle description, cached at
+    /// inputs/dayN.example[K] and fetched on first use
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "input",
+        value_name = "K",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u8).range(1..)
+    )]
+    example: Option<u8>,
+
+    /// Download inputs again even if they are already cached
+    #[arg(long, global = true)]
+    refresh: bool,
+
+    /// Use the session, inputs and answers of the profile in
+    /// profiles/NAME/ instead of the default ones
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        value_parser = Profile::named
+    )]
+    profile: Option<Profile>,
+
+    /// Give up on a part after this many seconds, overriding its day's
+    /// default
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        value_parser = parse_timeout
+    )]
+    timeout: Option<Duration>,
+
+    /// Log debug output from the solutions; `RUST_LOG=dayN=debug` narrows
+    /// it down to one day
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Also append the log to this file, one JSON object per record
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the selected days and print a summary table
+    ///
+    /// Exits with code 1 if any part gives an answer other than the accepted
+    /// one, has an invalid input or times out. Parts without an accepted
+    /// answer don't count.
+    Run {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Print the results as `text`, `json` or `csv`
+        #[arg(long, default_value = "text")]
+        format: Format,
+
+        /// Run the cached inputs of every profile, checking each against
+        /// that profile's answers. Days without a cached input are skipped
+        /// rather than fetched
+        #[arg(
+            long,
+            conflicts_with_all = ["profile", "input", "example", "refresh"]
+        )]
+        all_profiles: bool,
+    },
+    /// Time repeated runs of the selected days
+    Bench {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Untimed runs before measuring
+        #[arg(long, default_value_t = 1)]
+        warmup: usize,
+
+        /// Timed runs per part
+        #[arg(
+            long,
+            default_value_t = 10,
+            value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        runs: usize,
+
+        /// Write the measurements to this file
+        #[arg(long, value_name = "PATH")]
+        save: Option<PathBuf>,
+
+        /// Compare against measurements previously written with --save
+        #[arg(long, value_name = "PATH")]
+        baseline: Option<PathBuf>,
+    },
+    /// Download and cache the puzzle inputs of the selected days
+    Fetch {
+        /// Days to fetch
+        #[arg(
+            value_parser = parse_released_days,
+            required_unless_present = "all"
+        )]
+        days: Option<Days>,
+
+        /// Fetch every day that has unlocked so far
+        #[arg(long, conflicts_with = "days")]
+        all: bool,
+
+        /// Fetch the examples from the puzzle descriptions instead
+        #[arg(long)]
+        examples: bool,
+    },
+    /// Compute an answer and submit it, recording the verdict in `answers`
+    Submit {
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        part: Part,
+    },
+    /// Check the selected days against their accepted answers
+    ///
+    /// Days whose input isn't cached are skipped rather than fetched, unless
+    /// --refresh is given.
+    Test {
+        /// Days to check
+        #[arg(value_parser = parse_days, default_value = "all")]
+        days: Days,
+
+        /// Also check answers marked slow
+        #[arg(long)]
+        slow: bool,
+
+        /// Rewrite `answers`, keying answers accepted without an input to
+        /// the inputs they pass on
+        #[arg(long)]
+        adopt: bool,
+    },
+}
+
+#[derive(Args)]
+struct Selection {
+    /// Days to run: `all`, a day, a range such as `1-5`, or a list such as
+    /// `3,5`
+    #[arg(value_parser = parse_days)]
+    days: Days,
+
+    /// Part to run, 1 or 2; both when omitted
+    part: Option<Part>,
+}
+
+#[derive(Clone)]
+struct Days(Vec<u8>);
+
+fn parse_days(s: &str) -> Result<Days, String> {
+    select::parse_days(s, DAYS).map(Days)
+}
+
+fn parse_released_days(s: &str) -> Result<Days, String> {
+    select::parse_days(s, &input::released_days(SystemTime::now())).map(Days)
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    match Duration::try_from_secs_f64(secs) {
+        Ok(timeout) if !timeout.is_zero() => Ok(timeout),
+        _ => Err("expected a positive number of seconds".to_string()),
+    }
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    match select::parse_days(s, DAYS)?[..] {
+        [day] => Ok(day),
+        _ => Err("expected a single day".to_string()),
+    }
+}
+
+impl Cli {
+    fn command_with_days() -> clap::Command {
+        let days: Vec<String> = DAYS.iter().map(u8::to_string).collect();
+        let help = format!("Available days: {}", days.join(", "));
+
+        let command = Self::command();
+        let subcommands: Vec<String> = command
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect();
+        subcommands
+            .iter()
+            .fold(command, |command, name| {
+                command.mut_subcommand(name, |c| c.after_help(&help))
+            })
+            .after_help(&help)
+    }
+
+    fn parse_with_days() -> Self {
+        let matches = Self::command_with_days().get_matches();
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Prints `message` with the usage text and exits with a nonzero code.
+    fn exit_with_usage(message: impl std::fmt::Display) -> ! {
+        Self::command_with_days()
+            .error(ErrorKind::ArgumentConflict, message)
+            .exit()
+    }
+
+    fn profile(&self) -> Profile {
+        self.profile.clone().unwrap_or_default()
+    }
+
+    /// Decides where the selected `days` read their input from.
+    fn source(&self, days: &[u8]) -> Source {
+        let source = match (&self.input, self.example) {
+            (Some(path), _) if path.as_os_str() == "-" => Source::Stdin,
+            (Some(path), _) => Source::File(path.clone()),
+            (None, Some(k)) => Source::Example {
+                profile: self.profile(),
+                k,
+                refresh: self.refresh,
+            },
+            (None, None) => Source::Cache {
+                profile: self.profile(),
+                refresh: self.refresh,
+            },
+        };
+
+        if days.len() > 1 && matches!(source, Source::File(_) | Source::Stdin) {
+            Self::exit_with_usage("--input can only be used with a single day");
+        }
+        source
+    }
+}
+
+/// Where a day's puzzle input is read from.
+enum Source {
+    /// `dayN` in the profile's cache, fetched from the website on first
+    /// use.
+    Cache {
+        profile: Profile,
+        refresh: bool,
+    },
+    /// The `k`th example of `dayN` in the profile's cache, fetched with the
+    /// rest of the day's examples on first use.
+    Example {
+        profile: Profile,
+        k: u8,
+        refresh: bool,
+    },
+    File(PathBuf),
+    Stdin,
+}
+
+/// Prints `message` and exits with a nonzero code.
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1)
+}
+
+/// Prints `error` and exits with the code for its kind of failure.
+fn fail_input(error: InputError) -> ! {
+    eprintln!("error: {error}");
+    std::process::exit(error.exit_code())
+}
+
+fn client(profile: &Profile) -> Result<Client, InputError> {
+    Ok(Client::new(&profile.session()?))
+}
+
+fn read_cached_input(
+    day: u8,
+    profile: &Profile,
+    refresh: bool,
+) -> Result<String, InputError> {
+    let cache = profile.cache();
+    match cache.get(day)? {
+        Some(contents) if !refresh => Ok(contents),
+        _ => {
+            eprintln!("Fetching input for day {}...", day);
+            cache.fetch(day, &client(profile)?)
+        }
+    }
+}
+
+fn read_example(
+    day: u8,
+    profile: &Profile,
+    k: u8,
+    refresh: bool,
+) -> Result<String, InputError> {
+    let cache = profile.cache();
+    let path = cache.example_path(day, k);
+    if refresh || !path.exists() {
+        eprintln!("Fetching examples for day {}...", day);
+        cache.fetch_examples(day, &client(profile)?)?;
     }
+    std::fs::read_to_string(&path).map_err(|e| InputError::io(&path, e))
+}
 
-    let session = std::fs::read_to_string(".session")
-        .expect("could not read session file");
-    let url = format!("https://adventofcode.com/2023/day/{day}/input",);
+fn try_read_input(day: u8, source: &Source) -> Result<Input, InputError> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|e| InputError::io(path, e))
+    };
+    let contents = match source {
+        Source::Cache { profile, refresh } => {
+            read_cached_input(day, profile, *refresh)?
+        }
+        Source::Example {
+            profile,
+            k,
+            refresh,
+        } => read_example(day, profile, *k, *refresh)?,
+        Source::File(path) => read(path)?,
+        Source::Stdin => {
+            return Input::read(std::io::stdin())
+                .map_err(|e| InputError::io(Path::new("<stdin>"), e))
+        }
+    };
 
-    ureq::get(&url)
-        .set("Cookie", session.trim())
-        .call()
-        .expect("could not fetch input")
-        .into_string()
-        .expect("could not parse input")
+    Ok(Input::from(contents))
 }
 
-fn read_input(day: &str) -> Vec<String> {
-    if atty::is(atty::Stream::Stdin) {
-        if !std::path::Path::new("inputs").exists() {
-            std::fs::create_dir("inputs")
-                .expect("Failed to create inputs directory");
+fn read_input(day: u8, source: &Source) -> Input {
+    try_read_input(day, source).unwrap_or_else(|e| fail_input(e))
+}
+
+/// `day`'s input if `profile` has it cached, without fetching it.
+fn cached_input(day: u8, profile: &Profile) -> Option<Input> {
+    match profile.cache().get(day) {
+        Ok(contents) => Some(Input::from(contents?)),
+        Err(e) => {
+            eprintln!("profile {}: {e}", profile.name());
+            None
         }
+    }
+}
+
+fn load_answers(profile: &Profile) -> Answers {
+    Answers::load(&profile.answers_path()).unwrap_or_else(|e| fail(e))
+}
 
-        let filename = format!("inputs/day{day}");
+fn run(cli: &Cli, selection: &Selection, format: Format, all_profiles: bool) {
+    let days = &selection.days.0;
+    let parts = select::parts(selection.part);
+
+    let rows = match all_profiles {
+        true => Profile::all()
+            .unwrap_or_else(|e| fail(e))
+            .into_iter()
+            .flat_map(|profile| {
+                // Not every profile has every day cached, or unlocked.
+                let inputs = days
+                    .iter()
+                    .filter_map(|&day| {
+                        Some((day, cached_input(day, &profile)?))
+                    })
+                    .collect();
+                run_days(cli, &profile, &inputs, &parts, true)
+            })
+            .collect(),
+        false => {
+            let source = cli.source(days);
+            let inputs = days
+                .iter()
+                .map(|&day| (day, read_input(day, &source)))
+                .collect();
+            let own = matches!(source, Source::Cache { .. });
+            run_days(cli, &cli.profile(), &inputs, &parts, own)
+        }
+    };
+    println!("{}", report::render(&rows, format));
+    if rows.iter().any(|row| row.status().failed()) {
+        std::process::exit(1);
+    }
+}
 
-        let contents = match std::fs::read_to_string(&filename) {
-            Ok(contents) => contents,
-            Err(_) => {
-                let contents = fetch_input(day);
-                std::fs::write(&filename, &contents)
-                    .expect("Failed to write input file");
-                contents
+/// Runs `parts` of every day in `inputs`, checking the answers against the
+/// ones accepted for `profile`. `own` says whether the inputs are the
+/// profile's cached ones, see [`Answers::accepted`].
+fn run_days(
+    cli: &Cli,
+    profile: &Profile,
+    inputs: &BTreeMap<u8, Input>,
+    parts: &[Part],
+    own: bool,
+) -> Vec<Row> {
+    let answers = load_answers(profile);
+    let read_day = |day: u8| &inputs[&day];
+    let timeout = cli.timeout;
+
+    let mut rows = Vec::new();
+    for &day in inputs.keys() {
+        for &part in parts {
+            let output = run_puzzle!(read_day, day, part, timeout);
+            let input = inputs[&day].checksum();
+            let expected = answers
+                .accepted(day, part, input, own)
+                .map(|e| e.answer.clone());
+            rows.push(Row {
+                profile: profile.name().to_string(),
+                day,
+                part,
+                input,
+                expected,
+                output,
+            });
+        }
+    }
+    rows
+}
+
+fn bench(
+    cli: &Cli,
+    selection: &Selection,
+    warmup: usize,
+    runs: usize,
+    save: Option<&PathBuf>,
+    baseline: Option<&PathBuf>,
+) {
+    let days = &selection.days.0;
+    let parts = select::parts(selection.part);
+    let source = cli.source(days);
+    let baseline = baseline.map(|path| {
+        bench::load(path).unwrap_or_else(|e| Cli::exit_with_usage(e))
+    });
+
+    let inputs: HashMap<u8, Input> = days
+        .iter()
+        .map(|&day| (day, read_input(day, &source)))
+        .collect();
+    let read_day = |day: u8| &inputs[&day];
+    let timeout = cli.timeout;
+
+    let mut measurements = Vec::new();
+    for &day in days {
+        for &part in &parts {
+            let outputs: Result<Vec<_>, Failure> = (0..warmup + runs)
+                .map(|_| run_puzzle!(read_day, day, part, timeout))
+                .collect();
+            match outputs {
+                Ok(outputs) => measurements.push(Measurement::from(
+                    day,
+                    part,
+                    &outputs[warmup..],
+                )),
+                Err(e) => eprintln!("day {day} part {part}: {e}"),
             }
+        }
+    }
+
+    println!("{}", bench::table(&measurements, baseline.as_deref()));
+    if let Some(path) = save {
+        if let Err(e) = bench::save(path, &measurements) {
+            fail(format!("could not write {path:?}: {e}"));
+        }
+    }
+}
+
+fn fetch(cli: &Cli, days: &[u8], examples: bool) {
+    let profile = cli.profile();
+    let cache = profile.cache();
+    let client = client(&profile).unwrap_or_else(|e| fail_input(e));
+
+    let mut failed = None;
+    for &day in days {
+        let path = match examples {
+            true => cache.example_path(day, 1),
+            false => cache.input_path(day),
         };
+        if !cli.refresh && path.exists() {
+            println!("day {day}: already cached at {}", path.display());
+            continue;
+        }
 
-        contents
-            .trim()
-            .split('\n')
-            .map(|line| line.to_string())
-            .collect()
-    } else {
-        std::io::stdin().lines().map_while(Result::ok).collect()
+        let result = match examples {
+            true => cache.fetch_examples(day, &client),
+            false => cache.fetch(day, &client).map(|_| vec![path]),
+        };
+        match result {
+            Ok(paths) => {
+                for path in paths {
+                    println!("day {day}: fetched {}", path.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                failed = Some(e.exit_code());
+            }
+        }
+    }
+
+    if let Some(code) = failed {
+        std::process::exit(code);
     }
 }
 
-fn main() {
-    env_logger::builder().format_timestamp(None).init();
+fn submit(cli: &Cli, day: u8, part: Part) {
+    if cli.example.is_some() {
+        Cli::exit_with_usage("cannot submit the answer to an example");
+    }
+    let source = cli.source(&[day]);
+    let input = read_input(day, &source);
+    let read_day = |_: u8| &input;
+    let timeout = cli.timeout;
+    let answer = run_puzzle!(read_day, day, part, timeout)
+        .unwrap_or_else(|e| fail(format!("day {day} part {part}: {e}")))
+        .answer;
+    let checksum = input.checksum();
 
-    let args: Vec<String> = env::args().collect();
-    let day = args.get(1).expect("missing day argument");
-    let part = args.get(2).expect("missing part argument");
+    let profile = cli.profile();
+    let mut answers = load_answers(&profile);
+    let own = matches!(source, Source::Cache { .. });
+    match answers.check(day, part, checksum, own, &answer) {
+        Some(Verdict::Correct) => {
+            println!("day {day} part {part}: {answer} was already accepted");
+            return;
+        }
+        Some(verdict) => fail(format!(
+            "day {day} part {part}: {answer} is known to be wrong \
+            ({verdict}), not resubmitting"
+        )),
+        None => (),
+    }
 
-    let output = run_puzzle!(read_input, day, part);
-    println!("{:#?}", output);
+    println!("day {day} part {part}: submitting {answer}...");
+    let outcome = client(&profile)
+        .unwrap_or_else(|e| fail_input(e))
+        .submit(day, part, &answer)
+        .unwrap_or_else(|e| fail(e));
+    let verdict = match outcome {
+        Outcome::Correct => Verdict::Correct,
+        Outcome::TooHigh => Verdict::TooHigh,
+        Outcome::TooLow => Verdict::TooLow,
+        Outcome::Wrong => Verdict::Wrong,
+        Outcome::RateLimited(wait) => {
+            fail(format!("rate limited, try again in {wait:?}"))
+        }
+        Outcome::WrongLevel => {
+            fail("wrong level -- the part is already solved, or part 1 isn't")
+        }
+        Outcome::Unknown(text) => fail(format!("unknown response: {text}")),
+    };
+
+    answers.record(Entry {
+        day,
+        part,
+        input: Some(checksum),
+        verdict,
+        answer,
+        slow: false,
+    });
+    answers.save().unwrap_or_else(|e| fail(e));
+
+    println!("{verdict}");
+    if verdict != Verdict::Correct {
+        std::process::exit(1);
+    }
 }
 
+/// The result of checking one part against its accepted answer.
+enum Regression {
+    Pass,
+    Fail {
+        expected: Answer,
+        actual: Answer,
+    },
+    /// The part gave no answer to compare.
+    NoAnswer(Failure),
+    Skipped(&'static str),
+}
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn regression_day1() {
-        use advent_of_code_2023::day1;
-        let input = super::read_input("1");
-        assert_eq!(day1::part1(&input), 55834);
-        assert_eq!(day1::part2(&input), 53221);
+impl Regression {
+    fn failed(&self) -> bool {
+        matches!(self, Regression::Fail { .. } | Regression::NoAnswer(_))
     }
+}
 
-    #[test]
-    fn regression_day2() {
-        use advent_of_code_2023::day2;
-        let input = super::read_input("2");
-        assert_eq!(day2::part1(&input), 2683);
-        assert_eq!(day2::part2(&input), 49710);
+impl std::fmt::Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Regression::Pass => write!(f, "ok"),
+            Regression::Fail { expected, actual } => {
+                write!(f, "FAILED: expected {expected}, got {actual}")
+            }
+            Regression::NoAnswer(e) => write!(f, "FAILED: {e}"),
+            Regression::Skipped(reason) => write!(f, "skipped ({reason})"),
+        }
     }
+}
 
-    #[test]
-    fn regression_day3() {
-        use advent_of_code_2023::day3;
-        let input = super::read_input("3");
-        assert_eq!(day3::part1(&input), 527369);
-        assert_eq!(day3::part2(&input), 73074886);
+/// Recomputes the accepted answers of `days` for their inputs, reading
+/// each day's input with `read`, which returns `None` for days without one.
+/// `own` says whether those are the profile's cached inputs, which answers
+/// accepted without an input are for. Such answers are adopted in `answers`
+/// by the input they pass on, see [`Answers::adopt`], for the caller to save.
+fn regressions(
+    answers: &mut Answers,
+    days: &[u8],
+    slow: bool,
+    timeout: Option<Duration>,
+    own: bool,
+    mut read: impl FnMut(u8) -> Option<Input>,
+) -> Vec<(u8, Part, Regression)> {
+    let mut results = Vec::new();
+    for &day in days {
+        let mut input = None;
+        for part in [Part::One, Part::Two] {
+            let result = if !answers.any_accepted(day, part) {
+                Regression::Skipped("no accepted answer")
+            } else if let Some(input) = input.get_or_insert_with(|| read(day)) {
+                check_part(answers, day, part, input, slow, timeout, own)
+            } else {
+                Regression::Skipped("no input")
+            };
+            results.push((day, part, result));
+        }
     }
+    results
+}
+
+/// Checks `part` of `day` against the answer accepted for `input`.
+fn check_part(
+    answers: &mut Answers,
+    day: u8,
+    part: Part,
+    input: &Input,
+    slow: bool,
+    timeout: Option<Duration>,
+    own: bool,
+) -> Regression {
+    let checksum = input.checksum();
+    let expected = match answers.accepted(day, part, checksum, own) {
+        None => return Regression::Skipped("no known answer for this input"),
+        Some(entry) if entry.slow && !slow => {
+            return Regression::Skipped("slow")
+        }
+        Some(entry) => entry.answer.clone(),
+    };
+
+    let read_day = |_: u8| input;
+    let actual = match run_puzzle!(read_day, day, part, timeout) {
+        Ok(output) => output.answer,
+        Err(e) => return Regression::NoAnswer(e),
+    };
+    if expected == actual {
+        answers.adopt(day, part, checksum);
+        Regression::Pass
+    } else {
+        Regression::Fail { expected, actual }
+    }
+}
+
+fn test(cli: &Cli, days: &[u8], slow: bool, adopt: bool) {
+    let source = cli.source(days);
+    let mut answers = load_answers(&cli.profile());
+    let recorded = answers.entries().to_vec();
+    let own = matches!(source, Source::Cache { .. });
+    let results =
+        regressions(&mut answers, days, slow, cli.timeout, own, |day| {
+            match &source {
+                // Days never fetched are skipped rather than fetched now.
+                Source::Cache {
+                    profile,
+                    refresh: false,
+                } => cached_input(day, profile),
+                source => Some(read_input(day, source)),
+            }
+        });
+    if adopt && answers.entries() != recorded {
+        answers.save().unwrap_or_else(|e| fail(e));
+    }
+
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|(day, part, result)| {
+            vec![day.to_string(), part.to_string(), result.to_string()]
+        })
+        .collect();
+    println!(
+        "{}",
+        report::align(&["day", "part", "result"], &rows).join("\n")
+    );
+
+    let count = |f: fn(&Regression) -> bool| {
+        results.iter().filter(|(_, _, result)| f(result)).count()
+    };
+    let failed = count(Regression::failed);
+    println!(
+        "{} passed, {failed} failed, {} skipped",
+        count(|r| matches!(r, Regression::Pass)),
+        count(|r| matches!(r, Regression::Skipped(_))),
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
 
+fn main() {
+    let cli = Cli::parse_with_days();
+
+    let level = match cli.verbose {
+        true => log::LevelFilter::Debug,
+        false => log::LevelFilter::Error,
+    };
+    if let Err(e) = logging::init(level, cli.log_file.as_deref()) {
+        let path = cli.log_file.as_ref().unwrap();
+        fail(format!("could not open {path:?}: {e}"));
+    }
+
+    match &cli.command {
+        Command::Run {
+            selection,
+            format,
+            all_profiles,
+        } => run(&cli, selection, *format, *all_profiles),
+        Command::Bench {
+            selection,
+            warmup,
+            runs,
+            save,
+            baseline,
+        } => bench(
+            &cli,
+            selection,
+            *warmup,
+            *runs,
+            save.as_ref(),
+            baseline.as_ref(),
+        ),
+        Command::Fetch {
+            days: Some(days),
+            examples,
+            ..
+        } => fetch(&cli, &days.0, *examples),
+        Command::Fetch {
+            days: None,
+            examples,
+            ..
+        } => fetch(&cli, &input::released_days(SystemTime::now()), *examples),
+        Command::Submit { day, part } => submit(&cli, *day, *part),
+        Command::Test { days, slow, adopt } => {
+            test(&cli, &days.0, *slow, *adopt)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks every profile's accepted answers for its cached inputs,
+    /// skipping those accepted for other accounts' inputs. Answers marked
+    /// slow only run with `AOC_SLOW` set.
     #[test]
-    fn regression_day4() {
-        use advent_of_code_2023::day4;
-        let input = super::read_input("4");
-        assert_eq!(day4::part1(&input), 19855);
-        assert_eq!(day4::part2(&input), 10378710);
+    fn regression() {
+        let slow = std::env::var_os("AOC_SLOW").is_some();
+        let mut results = Vec::new();
+        for profile in Profile::all().unwrap() {
+            let mut answers = Answers::load(&profile.answers_path()).unwrap();
+            let checked =
+                regressions(&mut answers, DAYS, slow, None, true, |day| {
+                    cached_input(day, &profile)
+                });
+            for (day, part, result) in checked {
+                results.push((profile.name().to_string(), day, part, result));
+            }
+        }
+
+        let failures: Vec<String> = results
+            .iter()
+            .map(|(profile, day, part, result)| {
+                let line =
+                    format!("{profile}: day {day} part {part}: {result}");
+                eprintln!("{line}");
+                (line, result)
+            })
+            .filter(|(_, result)| result.failed())
+            .map(|(line, _)| line)
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
     }
 
     #[test]
-    fn regression_day5() {
-        use advent_of_code_2023::day5;
-        let input = super::read_input("5");
-        assert_eq!(day5::part1(&input), 389056265);
-        // Expensive test (~3 min):
-        // assert_eq!(day5::part2(&input), 137516820);
+    fn unkeyed_answers() {
+        let path = std::env::temp_dir().join("aoc-answers-unkeyed");
+        let mut answers = Answers::load(&path).unwrap();
+        answers.record("1 1 - correct 999".parse().unwrap());
+        let input = Input::from("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet");
+
+        // An answer accepted without an input is for the profile's own.
+        let mut check = |own| {
+            check_part(&mut answers, 1, Part::One, &input, false, None, own)
+        };
+        assert!(matches!(check(false), Regression::Skipped(_)));
+        match check(true) {
+            Regression::Fail { expected, actual } => {
+                assert_eq!(expected, 999.into());
+                assert_eq!(actual, 142.into());
+            }
+            result => panic!("expected a failure, got {result}"),
+        }
+        assert_eq!(answers.entries()[0].input, None);
     }
 }
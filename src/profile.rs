@@ -0,0 +1,158 @@
+use crate::client;
+use crate::input::{Cache, InputError};
+use std::path::{Path, PathBuf};
+
+/// The `profiles/` directory in the crate root, see [`Profile`].
+const PROFILES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/profiles");
+
+/// One Advent of Code account's session cookie, cached inputs and answers.
+///
+/// The default profile finds its cookie as described in
+/// [`client::session`], caches inputs as in [`Cache::from_env`] and keeps
+/// its answers in `answers` in the crate root. A named profile keeps all
+/// three as `session`, `inputs/` and `answers` in `profiles/<name>/`, or in
+/// `AOC_PROFILES_DIR` instead of `profiles/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    name: Option<String>,
+    dir: PathBuf,
+}
+
+fn profiles_dir() -> PathBuf {
+    match std::env::var_os("AOC_PROFILES_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(PROFILES),
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: None,
+            dir: PathBuf::from(env!("CARGO_MANIFEST_DIR")),
+        }
+    }
+}
+
+impl Profile {
+    /// The profile called `name`, whose directory has to exist already.
+    pub fn named(name: &str) -> Result<Self, String> {
+        Self::in_dir(&profiles_dir(), name)
+    }
+
+    fn in_dir(profiles: &Path, name: &str) -> Result<Self, String> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+            && !name.starts_with('.');
+        if !valid {
+            return Err(format!(
+                "invalid profile name {name:?} -- expected letters, digits, \
+                `-`, `_` and `.`"
+            ));
+        }
+
+        let dir = profiles.join(name);
+        if !dir.is_dir() {
+            return Err(format!(
+                "no profile {name:?} -- create {} holding its session cookie \
+                in a `session` file",
+                dir.display()
+            ));
+        }
+        Ok(Self {
+            name: Some(name.to_string()),
+            dir,
+        })
+    }
+
+    /// The default profile followed by every named one, sorted by name.
+    /// Directories that can't be profiles, such as hidden ones, are skipped.
+    pub fn all() -> Result<Vec<Self>, String> {
+        Self::all_in(&profiles_dir())
+    }
+
+    fn all_in(profiles: &Path) -> Result<Vec<Self>, String> {
+        let mut names: Vec<String> = match std::fs::read_dir(profiles) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("could not read {profiles:?}: {e}")),
+        };
+        names.sort();
+
+        let named = names
+            .iter()
+            .filter_map(|name| Self::in_dir(profiles, name).ok());
+        Ok([Self::default()].into_iter().chain(named).collect())
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+
+    pub fn session(&self) -> Result<String, InputError> {
+        match self.name {
+            None => client::session(),
+            Some(_) => client::session_file(&self.dir.join("session")),
+        }
+    }
+
+    pub fn cache(&self) -> Cache {
+        match self.name {
+            None => Cache::from_env(),
+            Some(_) => Cache::new(self.dir.join("inputs")),
+        }
+    }
+
+    /// Every answer submitted from this profile, see
+    /// [`Answers`](crate::answers::Answers). Accepted answers double as
+    /// regression tests.
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir.join("answers")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        let dir = std::env::temp_dir().join("aoc-profiles");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(Profile::all_in(&dir), Ok(vec![Profile::default()]));
+
+        std::fs::create_dir_all(dir.join("bob")).unwrap();
+        std::fs::create_dir_all(dir.join("alice")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::create_dir_all(dir.join("not a profile")).unwrap();
+        std::fs::write(dir.join("README"), "not a profile").unwrap();
+        std::fs::write(dir.join("alice/session"), "abc\n").unwrap();
+
+        let all = Profile::all_in(&dir).unwrap();
+        let names: Vec<&str> = all.iter().map(Profile::name).collect();
+        assert_eq!(names, ["default", "alice", "bob"]);
+
+        let alice = Profile::in_dir(&dir, "alice").unwrap();
+        assert_eq!(alice.session().unwrap(), "session=abc");
+        assert_eq!(
+            alice.cache().input_path(5),
+            dir.join("alice").join("inputs").join("day5")
+        );
+        assert_eq!(alice.answers_path(), dir.join("alice").join("answers"));
+        assert!(matches!(
+            Profile::in_dir(&dir, "bob").unwrap().session(),
+            Err(InputError::Io { .. })
+        ));
+
+        assert!(Profile::in_dir(&dir, "carol").is_err());
+        assert!(Profile::in_dir(&dir, "../alice").is_err());
+        assert!(Profile::in_dir(&dir, "").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,400 @@
+use crate::input::Checksum;
+use crate::solution::{Failure, Output, Part};
+use crate::Answer;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The result of running one part of one day.
+#[derive(Debug)]
+pub struct Row {
+    /// The name of the profile whose input and answers were used.
+    pub profile: String,
+    pub day: u8,
+    pub part: Part,
+    /// The checksum of the input the part ran on.
+    pub input: Checksum,
+    /// The accepted answer for that input, if there is one.
+    pub expected: Option<Answer>,
+    pub output: Result<Output, Failure>,
+}
+
+impl Row {
+    pub fn status(&self) -> Status {
+        match (&self.output, &self.expected) {
+            (Err(Failure::Invalid(_)), _) => Status::Invalid,
+            (Err(Failure::TimedOut(_)), _) => Status::TimedOut,
+            (Ok(_), None) => Status::Unknown,
+            (Ok(output), Some(expected)) if output.answer == *expected => {
+                Status::Pass
+            }
+            (Ok(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+/// How a [`Row`]'s answer compares with the accepted one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// There is no accepted answer to compare with.
+    Unknown,
+    /// The input couldn't be parsed.
+    Invalid,
+    TimedOut,
+}
+
+impl Status {
+    /// Whether the part went wrong, as opposed to passing or having
+    /// nothing to be checked against.
+    pub fn failed(self) -> bool {
+        matches!(self, Status::Fail | Status::Invalid | Status::TimedOut)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "fail"),
+            Status::Unknown => write!(f, "unknown"),
+            Status::Invalid => write!(f, "invalid"),
+            Status::TimedOut => write!(f, "timeout"),
+        }
+    }
+}
+
+/// How to print a run's rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// An aligned table, see [`table`].
+    Text,
+    /// An array with one object per row, see [`json`].
+    Json,
+    /// A header and one line per row, see [`csv`].
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "invalid format {s:?} -- expected text, json or csv"
+            )),
+        }
+    }
+}
+
+pub fn render(rows: &[Row], format: Format) -> String {
+    match format {
+        Format::Text => table(rows),
+        Format::Json => json(rows),
+        Format::Csv => csv(rows),
+    }
+}
+
+/// Lays `rows` out as an aligned table with a final line totalling the time
+/// spent across every run that finished. Rows only start with their profile
+/// if they come from more than one.
+pub fn table(rows: &[Row]) -> String {
+    let profiles = rows.iter().any(|row| row.profile != rows[0].profile);
+    let mut header =
+        vec!["day", "part", "answer", "parse", "solve", "input", "check"];
+    if profiles {
+        header.insert(0, "profile");
+    }
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.day.to_string(), row.part.to_string()];
+            if profiles {
+                cells.insert(0, row.profile.clone());
+            }
+            match &row.output {
+                Ok(output) => cells.extend([
+                    output.answer.to_string(),
+                    format!("{:.2?}", output.parse_time),
+                    format!("{:.2?}", output.solve_time),
+                ]),
+                Err(e) => cells.extend([e.to_string(), "-".into(), "-".into()]),
+            }
+            let check = match row.status() {
+                Status::Pass => "ok".to_string(),
+                Status::Fail => format!(
+                    "FAILED: expected {}",
+                    row.expected.as_ref().unwrap()
+                ),
+                Status::Unknown | Status::Invalid | Status::TimedOut => {
+                    String::new()
+                }
+            };
+            cells.extend([row.input.to_string(), check]);
+            cells
+        })
+        .collect();
+
+    let mut lines = align(&header, &cells);
+
+    let total: Duration = rows
+        .iter()
+        .filter_map(|row| row.output.as_ref().ok())
+        .map(|output| output.parse_time + output.solve_time)
+        .sum();
+    lines.push(format!("total: {total:.2?}"));
+
+    lines.join("\n")
+}
+
+/// A field of a [`Row`] as written by [`json`] and [`csv`].
+enum Value {
+    Number(String),
+    Text(String),
+    /// The answer and times of a part that gave no answer.
+    Null,
+}
+
+const FIELDS: [&str; 8] = [
+    "profile", "day", "part", "answer", "parse_ns", "solve_ns", "input",
+    "status",
+];
+
+/// The values of `row` in the order of [`FIELDS`]. Answers are always text
+/// since they may be too large for a JSON number, and times are whole
+/// nanoseconds.
+fn values(row: &Row) -> [Value; 8] {
+    let output = row.output.as_ref().ok();
+    let nanos =
+        |duration: Duration| Value::Number(duration.as_nanos().to_string());
+    [
+        Value::Text(row.profile.clone()),
+        Value::Number(row.day.to_string()),
+        Value::Number(row.part.to_string()),
+        output.map_or(Value::Null, |o| Value::Text(o.answer.to_string())),
+        output.map_or(Value::Null, |o| nanos(o.parse_time)),
+        output.map_or(Value::Null, |o| nanos(o.solve_time)),
+        Value::Text(row.input.to_string()),
+        Value::Text(row.status().to_string()),
+    ]
+}
+
+/// Writes `rows` as a JSON array with one object per line.
+pub fn json(rows: &[Row]) -> String {
+    let objects: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let members: Vec<String> = FIELDS
+                .iter()
+                .zip(values(row))
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::Number(n) => n,
+                        Value::Text(s) => json_string(&s),
+                        Value::Null => "null".to_string(),
+                    };
+                    format!("\"{name}\":{value}")
+                })
+                .collect();
+            format!("  {{{}}}", members.join(","))
+        })
+        .collect();
+
+    match objects.is_empty() {
+        true => "[]".to_string(),
+        false => format!("[\n{}\n]", objects.join(",\n")),
+    }
+}
+
+/// Writes `rows` as CSV with a header line, leaving the fields of parts
+/// that gave no answer empty.
+pub fn csv(rows: &[Row]) -> String {
+    let mut lines = vec![FIELDS.join(",")];
+    lines.extend(rows.iter().map(|row| {
+        values(row)
+            .map(|value| match value {
+                Value::Number(s) | Value::Text(s) => csv_field(&s),
+                Value::Null => String::new(),
+            })
+            .join(",")
+    }));
+    lines.join("\n")
+}
+
+/// Quotes `s` as a JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c < ' ' => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes `s` as a CSV field if it contains a separator, quote or newline.
+fn csv_field(s: &str) -> String {
+    match s.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => s.to_string(),
+    }
+}
+
+/// Pads every column of `header` and `rows` to a common width, returning
+/// one line per row with the header first.
+pub fn align(header: &[&str], rows: &[Vec<String>]) -> Vec<String> {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|row| row.get(i))
+                .map(String::len)
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |row: Vec<&str>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(header.to_vec())];
+    lines.extend(
+        rows.iter()
+            .map(|row| line(row.iter().map(String::as_str).collect())),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Input;
+
+    fn rows() -> Vec<Row> {
+        let output = |answer: &str| {
+            Ok(Output {
+                answer: answer.parse().unwrap(),
+                parse_time: Duration::from_micros(1500),
+                solve_time: Duration::from_nanos(20),
+            })
+        };
+        let input = Input::from("abc").checksum();
+        vec![
+            Row {
+                profile: "default".to_string(),
+                day: 1,
+                part: Part::One,
+                input,
+                expected: Some(142.into()),
+                output: output("142"),
+            },
+            Row {
+                profile: "default".to_string(),
+                day: 1,
+                part: Part::Two,
+                input,
+                expected: Some(281.into()),
+                output: output("28\"1"),
+            },
+            Row {
+                profile: "default".to_string(),
+                day: 5,
+                part: Part::Two,
+                input,
+                expected: None,
+                output: Err(Failure::TimedOut(Duration::from_secs(5))),
+            },
+        ]
+    }
+
+    #[test]
+    fn statuses() {
+        let statuses: Vec<Status> = rows().iter().map(Row::status).collect();
+        assert_eq!(statuses, [Status::Pass, Status::Fail, Status::TimedOut]);
+
+        let mut row = rows().remove(0);
+        row.expected = None;
+        assert_eq!(row.status(), Status::Unknown);
+        row.output = Err(Failure::Invalid("empty almanac".to_string()));
+        assert_eq!(row.status(), Status::Invalid);
+
+        let failed: Vec<bool> = [
+            Status::Pass,
+            Status::Fail,
+            Status::Unknown,
+            Status::Invalid,
+            Status::TimedOut,
+        ]
+        .map(Status::failed)
+        .to_vec();
+        assert_eq!(failed, [false, true, false, true, true]);
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(
+            render(&rows(), "json".parse().unwrap()),
+            [
+                "[",
+                r#"  {"profile":"default","day":1,"part":1,"answer":"142","#,
+                r#""parse_ns":1500000,"#,
+                r#""solve_ns":20,"input":"e71fa2190541574b","status":"pass"},"#,
+                r#"  {"profile":"default","day":1,"part":2,"answer":"28\"1","#,
+                r#""parse_ns":1500000,"#,
+                r#""solve_ns":20,"input":"e71fa2190541574b","status":"fail"},"#,
+                r#"  {"profile":"default","day":5,"part":2,"answer":null,"#,
+                r#""parse_ns":null,"#,
+                r#""solve_ns":null,"input":"e71fa2190541574b","#,
+                r#""status":"timeout"}"#,
+                "]",
+            ]
+            .join("\n")
+            .replace(",\n\"", ",\""),
+        );
+        assert_eq!(json(&[]), "[]");
+
+        assert_eq!(
+            render(&rows(), "csv".parse().unwrap()),
+            "profile,day,part,answer,parse_ns,solve_ns,input,status\n\
+            default,1,1,142,1500000,20,e71fa2190541574b,pass\n\
+            default,1,2,\"28\"\"1\",1500000,20,e71fa2190541574b,fail\n\
+            default,5,2,,,,e71fa2190541574b,timeout"
+        );
+
+        let table = render(&rows(), Format::Text);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[1].split_whitespace().last(), Some("ok"));
+        assert!(lines[2].ends_with("FAILED: expected 281"));
+        assert!(lines[3].contains("timed out after 5s  -"));
+        assert_eq!(lines[4], "total: 3.00ms");
+        assert!(lines[0].starts_with("day "));
+
+        let mut rows = rows();
+        rows[2].profile = "alice".to_string();
+        let text = render(&rows, Format::Text);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("profile  day "));
+        assert!(lines[3].starts_with("alice    5 "));
+
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
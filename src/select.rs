@@ -0,0 +1,78 @@
+use crate::solution::Part;
+
+/// Parses a day selector: `all`, a single day, an inclusive range such as
+/// `1-5`, or a comma-separated list of those such as `1-3,5`.
+///
+/// Every selected day must be one of `available`.
+pub fn parse_days(spec: &str, available: &[u8]) -> Result<Vec<u8>, String> {
+    if spec == "all" {
+        return Ok(available.to_vec());
+    }
+
+    let parse_day = |s: &str| {
+        s.trim()
+            .parse::<u8>()
+            .map_err(|_| format!("invalid day {s:?}"))
+    };
+
+    let mut days = Vec::new();
+    for item in spec.split(',') {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                if start > end {
+                    return Err(format!("invalid day range {item:?}"));
+                }
+                days.extend(start..=end);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+
+    if let Some(day) = days.iter().find(|day| !available.contains(day)) {
+        return Err(format!("day {day} is not available"));
+    }
+
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+/// Expands an optional part selector, defaulting to both parts.
+pub fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVAILABLE: &[u8] = &[1, 2, 3, 4, 5];
+
+    #[test]
+    fn days() {
+        assert_eq!(parse_days("all", AVAILABLE), Ok(AVAILABLE.to_vec()));
+        assert_eq!(parse_days("3", AVAILABLE), Ok(vec![3]));
+        assert_eq!(parse_days("2-4", AVAILABLE), Ok(vec![2, 3, 4]));
+        assert_eq!(parse_days("5,3", AVAILABLE), Ok(vec![3, 5]));
+        assert_eq!(parse_days("1-2,2,4", AVAILABLE), Ok(vec![1, 2, 4]));
+    }
+
+    #[test]
+    fn invalid_days() {
+        assert!(parse_days("6", AVAILABLE).is_err());
+        assert!(parse_days("4-7", AVAILABLE).is_err());
+        assert!(parse_days("3-1", AVAILABLE).is_err());
+        assert!(parse_days("one", AVAILABLE).is_err());
+        assert!(parse_days("", AVAILABLE).is_err());
+    }
+
+    #[test]
+    fn parts() {
+        assert_eq!(super::parts(None), vec![Part::One, Part::Two]);
+        assert_eq!(super::parts(Some(Part::Two)), vec![Part::Two]);
+    }
+}
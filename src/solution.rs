@@ -0,0 +1,256 @@
+use crate::common::CancelToken;
+use crate::logging::Span;
+use crate::{Answer, Input};
+use std::error::Error;
+use std::fmt;
+use std::panic;
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A day's puzzle, split into a parsing stage shared by both parts and the
+/// two parts themselves.
+pub trait Solution {
+    type Parsed;
+
+    /// How long a part may run before it is cancelled, unless a timeout is
+    /// given on the command line.
+    const TIMEOUT: Option<Duration> = None;
+
+    fn parse(input: &Input) -> Result<Self::Parsed, Box<dyn Error>>;
+
+    /// Checks that `parsed` has what `part` needs, for inputs only one of
+    /// the parts can make sense of.
+    fn validate(
+        _parsed: &Self::Parsed,
+        _part: Part,
+    ) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer;
+
+    fn part2(parsed: &Self::Parsed) -> Answer;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part {s:?} -- expected 1 or 2")),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Output {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.answer)?;
+        writeln!(f, "parse: {:?}", self.parse_time)?;
+        write!(f, "solve: {:?}", self.solve_time)
+    }
+}
+
+/// Parses `input` and runs `part` of `S`, timing each stage and logging
+/// it within a [`Span`]. Fails if `input` is invalid for `part`.
+pub fn solve<S: Solution>(
+    input: &Input,
+    part: Part,
+) -> Result<Output, Failure> {
+    let _part = Span::part(part).enter();
+
+    let start = Instant::now();
+    let parsed = {
+        let _stage = Span::stage("parse").enter();
+        S::parse(input)
+            .and_then(|parsed| S::validate(&parsed, part).map(|()| parsed))
+            .map_err(|e| Failure::Invalid(e.to_string()))?
+    };
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let answer = {
+        let _stage = Span::stage("solve").enter();
+        match part {
+            Part::One => S::part1(&parsed),
+            Part::Two => S::part2(&parsed),
+        }
+    };
+    let solve_time = start.elapsed();
+    log::debug!("answered {answer} in {parse_time:?} + {solve_time:?}");
+
+    Ok(Output {
+        answer,
+        parse_time,
+        solve_time,
+    })
+}
+
+/// Why a part gave no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    /// The input couldn't be parsed, for the given reason.
+    Invalid(String),
+    /// The part was cancelled for running longer than its timeout.
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Invalid(reason) => write!(f, "invalid input: {reason}"),
+            Failure::TimedOut(timeout) => {
+                write!(f, "timed out after {}s", timeout.as_secs_f64())
+            }
+        }
+    }
+}
+
+impl Error for Failure {}
+
+/// Like [`solve`], but gives up once `timeout`, or else `S::TIMEOUT`, has
+/// passed. The part then runs on its own thread, whose [`CancelToken`] is
+/// cancelled on timeout; work that doesn't check it is left running in the
+/// background.
+pub fn run<S: Solution + 'static>(
+    input: &Input,
+    part: Part,
+    timeout: Option<Duration>,
+) -> Result<Output, Failure> {
+    let Some(timeout) = timeout.or(S::TIMEOUT) else {
+        return solve::<S>(input, part);
+    };
+
+    let token = CancelToken::new();
+    let (sender, receiver) = mpsc::channel();
+    let worker = {
+        let token = token.clone();
+        let input = input.clone();
+        let span = Span::current();
+        thread::Builder::new()
+            .name(format!("part{part}"))
+            .spawn(move || {
+                CancelToken::set_current(token);
+                let _span = span.enter();
+                let _ = sender.send(solve::<S>(&input, part));
+            })
+            .expect("could not spawn a thread for the part")
+    };
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            Err(Failure::TimedOut(timeout))
+        }
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(payload) => panic::resume_unwind(payload),
+            Ok(()) => unreachable!("the worker always sends its output"),
+        },
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::common::cancelled;
+    use crate::input::Cache;
+
+    /// Asserts that `S` gives the same answers for `example` whether it is
+    /// read from the cache or from stdin, and with CRLF line endings or
+    /// trailing whitespace.
+    pub(crate) fn assert_consistent<S: Solution>(example: &str) {
+        let answers = |input: &Input| {
+            [Part::One, Part::Two]
+                .map(|part| solve::<S>(input, part).unwrap().answer)
+        };
+        let expected = answers(&Input::from(example));
+
+        let name = std::any::type_name::<S>().replace("::", "-");
+        let dir = std::env::temp_dir().join(format!("aoc-consistent-{name}"));
+        let cache = Cache::new(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(cache.input_path(1), format!("{example}\n")).unwrap();
+        let cached = Input::from(cache.get(1).unwrap().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let crlf = example.replace('\n', "\r\n");
+        let padded: String =
+            example.lines().map(|line| format!("{line} \t\n")).collect();
+        let variants = [
+            ("cache", cached),
+            ("stdin", Input::read(example.as_bytes()).unwrap()),
+            ("CRLF", Input::from(crlf.as_str())),
+            ("CRLF stdin", Input::read(crlf.as_bytes()).unwrap()),
+            ("trailing whitespace", Input::from(padded + "\n\n")),
+        ];
+        for (variant, input) in variants {
+            assert_eq!(answers(&input), expected, "{variant} input");
+        }
+    }
+
+    /// Part 1 answers straight away, part 2 spins until it is cancelled.
+    struct Spin;
+
+    impl Solution for Spin {
+        type Parsed = ();
+
+        const TIMEOUT: Option<Duration> = Some(Duration::from_millis(50));
+
+        fn parse(_input: &Input) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn part1(_parsed: &()) -> Answer {
+            Answer::from(1_u8)
+        }
+
+        fn part2(_parsed: &()) -> Answer {
+            while !cancelled() {
+                thread::yield_now();
+            }
+            Answer::from(2_u8)
+        }
+    }
+
+    #[test]
+    fn timeouts() {
+        let input = Input::from("");
+        let output = run::<Spin>(&input, Part::One, None).unwrap();
+        assert_eq!(output.answer, Answer::from(1_u8));
+
+        let err = run::<Spin>(&input, Part::Two, None).unwrap_err();
+        assert_eq!(err, Failure::TimedOut(Duration::from_millis(50)));
+        assert_eq!(err.to_string(), "timed out after 0.05s");
+
+        let timeout = Some(Duration::from_millis(10));
+        let err = run::<Spin>(&input, Part::Two, timeout).unwrap_err();
+        assert_eq!(err, Failure::TimedOut(Duration::from_millis(10)));
+        assert!(!cancelled());
+    }
+}